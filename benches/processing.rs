@@ -0,0 +1,96 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::fmt::Write;
+use std::hint::black_box;
+use transaction_processor::{process_transactions_from_str, HashAlgorithm, ProcessorConfig};
+
+const SIZES: [u64; 3] = [100_000, 1_000_000, 10_000_000];
+const CLIENTS: u64 = 1_000;
+
+// small xorshift generator so every run benchmarks exactly the same input
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+// roughly 70% deposits, 20% withdrawals and 10% disputes on earlier transactions
+fn generate_csv(records: u64) -> String {
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+    let mut csv = String::with_capacity(records as usize * 32);
+    csv.push_str("type,client,tx,amount\n");
+    for tx in 1..=records {
+        let client = rng.next() % CLIENTS + 1;
+        let amount = rng.next() % 100_000;
+        match rng.next() % 10 {
+            0..=6 => writeln!(
+                csv,
+                "deposit,{},{},{}.{:04}",
+                client,
+                tx,
+                amount / 100,
+                amount % 100
+            ),
+            7 | 8 => writeln!(
+                csv,
+                "withdrawal,{},{},{}.{:04}",
+                client,
+                tx,
+                amount / 1_000,
+                amount % 100
+            ),
+            _ => writeln!(csv, "dispute,{},{},", client, rng.next() % tx + 1),
+        }
+        .unwrap();
+    }
+    csv
+}
+
+fn bench_process_transactions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("process_transactions");
+    group.sample_size(10);
+    for records in SIZES {
+        let input = generate_csv(records);
+        group.throughput(Throughput::Elements(records));
+        group.bench_with_input(BenchmarkId::from_parameter(records), &input, |b, input| {
+            b.iter(|| {
+                process_transactions_from_str(black_box(input), &ProcessorConfig::default())
+                    .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+// the same input under every hash algorithm of the client and transaction maps
+fn bench_hash_algorithms(c: &mut Criterion) {
+    let mut group = c.benchmark_group("hash_algorithm");
+    group.sample_size(10);
+    let records = SIZES[1];
+    let input = generate_csv(records);
+    group.throughput(Throughput::Elements(records));
+
+    let algorithms = [
+        ("default", HashAlgorithm::Default),
+        ("ahash", HashAlgorithm::AHash),
+        #[cfg(feature = "fxhash")]
+        ("fx", HashAlgorithm::Fx),
+    ];
+    for (name, hash_algorithm) in algorithms {
+        let config = ProcessorConfig {
+            hash_algorithm,
+            ..Default::default()
+        };
+        group.bench_with_input(BenchmarkId::from_parameter(name), &input, |b, input| {
+            b.iter(|| process_transactions_from_str(black_box(input), &config).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_process_transactions, bench_hash_algorithms);
+criterion_main!(benches);
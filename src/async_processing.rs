@@ -0,0 +1,82 @@
+#[cfg(feature = "futures")]
+use crate::TransactionEntryOwned;
+use crate::{
+    is_blank_record, reader_builder, validate_processor_config, ClientReport, Error,
+    ProcessorConfig, TransactionProcessor,
+};
+use csv::{ByteRecord, Position, Reader};
+#[cfg(feature = "futures")]
+use futures::{Stream, StreamExt};
+use std::io::{Cursor, SeekFrom};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+
+// async counterpart of process_transactions_from_reader for use inside tokio tasks without a
+// spawn_blocking wrapper. the input is parsed line by line, so quoted fields spanning several
+// lines are not supported
+pub async fn process_transactions_async<R>(
+    reader: R,
+    config: &ProcessorConfig,
+) -> Result<ClientReport, Error>
+where
+    R: AsyncRead + Unpin,
+{
+    validate_processor_config(config).map_err(Error::InvalidConfig)?;
+
+    let mut lines = BufReader::new(reader).lines();
+    let mut headers: Option<ByteRecord> = None;
+    let mut raw_record = ByteRecord::new();
+    let mut line_reader = reader_builder()
+        .has_headers(false)
+        .from_reader(Cursor::new(Vec::new()));
+    let mut processor = TransactionProcessor::new(config);
+
+    while let Some(line) = lines.next_line().await? {
+        // blank lines hold no record
+        if !parse_line(&mut line_reader, &line, &mut raw_record)? {
+            continue;
+        }
+
+        match &headers {
+            Some(headers) => processor.apply_byte_record(&raw_record, headers)?,
+            None => headers = Some(raw_record.clone()),
+        }
+    }
+
+    Ok(processor.into_report())
+}
+
+// process records coming from any async source in place of the csv reader. an error from the
+// stream is handled like a record that cannot be parsed, following the configured error policy
+#[cfg(feature = "futures")]
+pub async fn process_stream<S>(stream: S, config: ProcessorConfig) -> Result<ClientReport, Error>
+where
+    S: Stream<Item = Result<TransactionEntryOwned, Error>>,
+{
+    validate_processor_config(&config).map_err(Error::InvalidConfig)?;
+
+    let mut stream = std::pin::pin!(stream);
+    let mut processor = TransactionProcessor::new(&config);
+    while let Some(entry) = stream.next().await {
+        match entry {
+            Ok(entry) => processor.apply_entry(&entry)?,
+            Err(e) => processor.fail_row(e)?,
+        }
+    }
+
+    Ok(processor.into_report())
+}
+
+// the same reader and its buffers are reused for every line. seeking back to the start resets
+// the parser, including its end of input state
+fn parse_line(
+    rdr: &mut Reader<Cursor<Vec<u8>>>,
+    line: &str,
+    raw_record: &mut ByteRecord,
+) -> Result<bool, Error> {
+    let input = rdr.get_mut().get_mut();
+    input.clear();
+    input.extend_from_slice(line.as_bytes());
+    rdr.seek_raw(SeekFrom::Start(0), Position::new())?;
+    let has_record = rdr.read_byte_record(raw_record)?;
+    Ok(has_record && !is_blank_record(raw_record))
+}
@@ -0,0 +1,40 @@
+use crate::Error;
+use csv::ByteRecord;
+
+// the fields covered by the checksum column, in order
+const CHECKSUM_FIELDS: [&[u8]; 4] = [b"type", b"client", b"tx", b"amount"];
+
+// compare the checksum column of a record with the crc32 of its type, client, tx and amount
+// fields as written, joined by commas. the checksum is 8 hex digits in either case
+pub(crate) fn verify_checksum(
+    raw_record: &ByteRecord,
+    headers: &ByteRecord,
+    row: u64,
+) -> Result<(), Error> {
+    let field = |name: &[u8]| {
+        headers
+            .iter()
+            .position(|header| header == name)
+            .and_then(|index| raw_record.get(index))
+            .unwrap_or_default()
+    };
+
+    let mut hasher = crc32fast::Hasher::new();
+    for (i, name) in CHECKSUM_FIELDS.iter().enumerate() {
+        if i > 0 {
+            hasher.update(b",");
+        }
+        hasher.update(field(name));
+    }
+    let computed = format!("{:08x}", hasher.finalize());
+
+    let expected = String::from_utf8_lossy(field(b"checksum"));
+    if !expected.eq_ignore_ascii_case(&computed) {
+        return Err(Error::ChecksumMismatch {
+            row,
+            expected: expected.into_owned(),
+            computed,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,306 @@
+use crate::{Amount, TransactionType};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use thiserror::Error as ThisError;
+
+// how records that break a processing rule are handled
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum ErrorPolicy {
+    // log the record and continue with the next one
+    #[default]
+    Lenient,
+    // stop processing and return the error
+    Strict,
+    // like lenient, and records that cannot be parsed or applied are collected along with their
+    // row number instead of stopping the processing
+    CollectErrors,
+}
+
+// what happens when a record leaves the available funds of a client below zero, like a dispute of
+// funds that were already withdrawn
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum BelowZeroPolicy {
+    // log a warning and continue
+    #[default]
+    Warn,
+    // set available to zero, lowering total by the same amount
+    Clamp,
+    // stop processing with Error::NegativeBalance
+    Abort,
+}
+
+// which entry merge_tx_maps keeps when both maps hold the same transaction id
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DuplicateTransactionPolicy {
+    // keep the entry of the first map
+    UseFirst,
+    // keep the entry of the second map
+    UseLatest,
+    // return Error::ConflictingTransaction
+    FailFast,
+}
+
+// the hash function of the maps holding the clients and transactions while processing
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum HashAlgorithm {
+    // SipHash from the standard library, resistant to inputs crafted to collide
+    #[default]
+    Default,
+    // FxHash, the fastest on integer keys but easy to flood with colliding ids
+    #[cfg(feature = "fxhash")]
+    Fx,
+    // aHash, close to FxHash in speed while keeping a random seed
+    AHash,
+}
+
+// called with the id of a client about to be created. Sync so that a config can still be shared
+// by the threads of process_transactions_parallel
+pub type NewClientHook = Box<dyn Fn(u16) + Send + Sync>;
+
+// called with the client, the tx and the amount of every applied chargeback, for webhooks or
+// fraud reviews. Sync for the same reason as NewClientHook
+pub type ChargebackHook = Box<dyn Fn(u16, u32, Amount) + Send + Sync>;
+
+// an on_chargeback hook that only logs the chargeback as a warning
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LoggingChargebackNotifier;
+
+impl LoggingChargebackNotifier {
+    pub fn notify(&self, client: u16, tx: u32, amount: Amount) {
+        log::warn!("chargeback of {} on tx {} of client {}", amount, tx, client);
+    }
+}
+
+impl From<LoggingChargebackNotifier> for ChargebackHook {
+    fn from(notifier: LoggingChargebackNotifier) -> Self {
+        Box::new(move |client, tx, amount| notifier.notify(client, tx, amount))
+    }
+}
+
+// options controlling how the transactions are processed
+#[derive(Default)]
+pub struct ProcessorConfig {
+    pub error_policy: ErrorPolicy,
+    // with the `logging` feature, log every operation on this client along with its balances
+    // before and after
+    pub debug_client: Option<u16>,
+    // the maximum total balance of a client, the part of a deposit above it is rejected. a
+    // transfer that would take its target above it is rejected in full
+    pub client_balance_cap: Option<Amount>,
+    // after a withdrawal or a transfer out, further withdrawals and transfers out of the same client
+    // are skipped for this many rows
+    pub withdrawal_cooldown_rows: Option<u64>,
+    // keep at most this many transactions besides those under an open dispute or pending review,
+    // evicting the least recently used one. this bounds the memory used on large inputs, but an
+    // evicted transaction can no longer be disputed and its id can be reused by a later deposit or
+    // withdrawal. transactions under an active dispute are never evicted, so that their held
+    // funds can be resolved or charged back, and are not counted against the size until the
+    // dispute ends. only use it when disputes are known to arrive shortly after their transaction
+    pub tx_cache_size: Option<usize>,
+    // print a progress line to stderr every this many rows
+    pub print_progress_every_n: Option<u64>,
+    // disputes of transactions of these types are skipped. transfers can never be disputed
+    pub disable_dispute_for_types: HashSet<TransactionType>,
+    // the first deposit of a client must be at least this amount, otherwise the client is not
+    // created. this includes a transfer to a new client. later deposits are not restricted
+    pub client_creation_requires_minimum_deposit: Option<Amount>,
+    // disputes of more than this amount are moved to DisputeStage::PendingReview instead of
+    // holding the funds, see there for how a review ends
+    pub max_dispute_amount: Option<Amount>,
+    // called before the first deposit or transfer to a client creates it, for checks against
+    // external systems. the record is skipped when the hook panics or sets reject_new_client
+    pub on_new_client: Option<NewClientHook>,
+    // set by on_new_client to reject the client, cleared before every call. the flag is shared by
+    // every processor using this config, so the shards of process_transactions_parallel should
+    // not use it
+    pub reject_new_client: Arc<AtomicBool>,
+    // called after every applied chargeback. a panic of the hook is caught and logged
+    pub on_chargeback: Option<ChargebackHook>,
+    // record every operation on the accounts in ClientReport::event_log, so the history of each
+    // account can be replayed
+    pub record_event_log: bool,
+    // skip exact repeats of an earlier raw record, like rows repeated by a retrying upstream
+    // system. the repeats are collected in the report without failing, even in strict mode. a
+    // hash of every distinct record is kept for the whole run, so the memory used grows with the
+    // input and is not bounded by tx_cache_size
+    pub skip_duplicate_records: bool,
+    pub hash_algorithm: HashAlgorithm,
+    // every record must have an amount, including disputes, resolves and chargebacks, to catch
+    // exports that drop the column. the amount of a dispute is checked against its transaction
+    pub require_amount_for_all_types: bool,
+    // append every ProcessingEvent to this file as a json line along with its row, without
+    // setting up an observer. the events of every run accumulate in the file
+    pub audit_trail_path: Option<PathBuf>,
+    // fail on a record whose checksum column is not the crc32 of its type, client, tx and amount
+    // fields joined by commas, as 8 hex digits
+    #[cfg(feature = "checksum")]
+    pub verify_checksums: bool,
+    // deducted from the available funds of merchant_client_id on every applied chargeback. the
+    // chargeback is applied even when the merchant cannot pay the fee
+    pub fee_on_chargeback: Option<Amount>,
+    // required when fee_on_chargeback is set
+    pub merchant_client_id: Option<u16>,
+    pub on_balance_below_zero: BelowZeroPolicy,
+}
+
+// the hooks cannot be printed, only whether they are set
+impl fmt::Debug for ProcessorConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("ProcessorConfig");
+        debug
+            .field("error_policy", &self.error_policy)
+            .field("debug_client", &self.debug_client)
+            .field("client_balance_cap", &self.client_balance_cap)
+            .field("withdrawal_cooldown_rows", &self.withdrawal_cooldown_rows)
+            .field("tx_cache_size", &self.tx_cache_size)
+            .field("print_progress_every_n", &self.print_progress_every_n)
+            .field("disable_dispute_for_types", &self.disable_dispute_for_types)
+            .field(
+                "client_creation_requires_minimum_deposit",
+                &self.client_creation_requires_minimum_deposit,
+            )
+            .field("max_dispute_amount", &self.max_dispute_amount)
+            .field("on_new_client", &self.on_new_client.is_some())
+            .field("reject_new_client", &self.reject_new_client)
+            .field("on_chargeback", &self.on_chargeback.is_some())
+            .field("record_event_log", &self.record_event_log)
+            .field("skip_duplicate_records", &self.skip_duplicate_records)
+            .field("hash_algorithm", &self.hash_algorithm)
+            .field(
+                "require_amount_for_all_types",
+                &self.require_amount_for_all_types,
+            )
+            .field("audit_trail_path", &self.audit_trail_path);
+        #[cfg(feature = "checksum")]
+        debug.field("verify_checksums", &self.verify_checksums);
+        debug
+            .field("fee_on_chargeback", &self.fee_on_chargeback)
+            .field("merchant_client_id", &self.merchant_client_id)
+            .field("on_balance_below_zero", &self.on_balance_below_zero)
+            .finish()
+    }
+}
+
+#[derive(Debug, PartialEq, ThisError)]
+pub enum ConfigError {
+    #[error("{a} and {b} cannot be used together")]
+    ConflictingPolicies { a: &'static str, b: &'static str },
+    #[error("Invalid value {value} for {field}")]
+    InvalidDecimalValue { field: &'static str, value: Amount },
+    #[error("Invalid size {value} for {field}")]
+    InvalidSize { field: &'static str, value: u64 },
+    #[error("Invalid path {path:?} for {field}")]
+    InvalidPath { field: &'static str, path: PathBuf },
+    #[error("Missing required field {field}")]
+    MissingRequiredField { field: &'static str },
+}
+
+// check every combination of options before any transaction is processed, collecting all of the
+// problems instead of stopping at the first one
+pub fn validate_processor_config(config: &ProcessorConfig) -> Result<(), Vec<ConfigError>> {
+    let mut errors = Vec::new();
+
+    // destructured so that a new option does not compile until it has been considered here, the
+    // options bound to _ accept every value
+    let ProcessorConfig {
+        error_policy: _,
+        debug_client: _,
+        client_balance_cap,
+        withdrawal_cooldown_rows: _,
+        tx_cache_size,
+        print_progress_every_n,
+        disable_dispute_for_types: _,
+        client_creation_requires_minimum_deposit,
+        max_dispute_amount,
+        on_new_client: _,
+        reject_new_client: _,
+        on_chargeback: _,
+        record_event_log: _,
+        skip_duplicate_records: _,
+        hash_algorithm: _,
+        require_amount_for_all_types: _,
+        audit_trail_path,
+        #[cfg(feature = "checksum")]
+            verify_checksums: _,
+        fee_on_chargeback,
+        merchant_client_id,
+        on_balance_below_zero: _,
+    } = config;
+
+    if let Some(cap) = *client_balance_cap {
+        if cap <= Amount::ZERO {
+            errors.push(ConfigError::InvalidDecimalValue {
+                field: "client_balance_cap",
+                value: cap,
+            });
+        }
+    }
+
+    if let Some(fee) = *fee_on_chargeback {
+        if fee <= Amount::ZERO {
+            errors.push(ConfigError::InvalidDecimalValue {
+                field: "fee_on_chargeback",
+                value: fee,
+            });
+        }
+        if merchant_client_id.is_none() {
+            errors.push(ConfigError::MissingRequiredField {
+                field: "merchant_client_id",
+            });
+        }
+    }
+
+    if let Some(minimum) = *client_creation_requires_minimum_deposit {
+        if minimum < Amount::ZERO {
+            errors.push(ConfigError::InvalidDecimalValue {
+                field: "client_creation_requires_minimum_deposit",
+                value: minimum,
+            });
+        }
+    }
+
+    if let Some(max) = *max_dispute_amount {
+        if max <= Amount::ZERO {
+            errors.push(ConfigError::InvalidDecimalValue {
+                field: "max_dispute_amount",
+                value: max,
+            });
+        }
+    }
+
+    if *tx_cache_size == Some(0) {
+        errors.push(ConfigError::InvalidSize {
+            field: "tx_cache_size",
+            value: 0,
+        });
+    }
+
+    if *print_progress_every_n == Some(0) {
+        errors.push(ConfigError::InvalidSize {
+            field: "print_progress_every_n",
+            value: 0,
+        });
+    }
+
+    // the file itself is created on the first event
+    if let Some(path) = audit_trail_path {
+        let missing_parent = path
+            .parent()
+            .is_some_and(|parent| !parent.as_os_str().is_empty() && !parent.is_dir());
+        if path.is_dir() || missing_parent {
+            errors.push(ConfigError::InvalidPath {
+                field: "audit_trail_path",
+                path: path.clone(),
+            });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
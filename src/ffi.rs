@@ -0,0 +1,62 @@
+use crate::{process_csv_bytes, write_client_info_to_writer, ProcessorConfig};
+use std::{ptr, slice};
+
+// returned by process_csv_bytes_ffi
+pub const FFI_OK: i32 = 0;
+pub const FFI_NULL_POINTER: i32 = 1;
+pub const FFI_PROCESSING_ERROR: i32 = 2;
+
+/// Processes the `len` bytes at `ptr` as a csv of transactions with the default config.
+///
+/// On `FFI_OK` the client csv is written to a buffer stored in `out_ptr` and `out_len`, on
+/// `FFI_PROCESSING_ERROR` the buffer holds the error message instead. Either buffer must be
+/// released with `process_csv_bytes_free`. On `FFI_NULL_POINTER` nothing is written.
+///
+/// # Safety
+///
+/// `ptr` must be valid for reads of `len` bytes, or may be null when `len` is 0. `out_ptr` and
+/// `out_len` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn process_csv_bytes_ffi(
+    ptr: *const u8,
+    len: usize,
+    out_ptr: *mut *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    if (ptr.is_null() && len > 0) || out_ptr.is_null() || out_len.is_null() {
+        return FFI_NULL_POINTER;
+    }
+    let bytes = match len {
+        0 => &[][..],
+        _ => slice::from_raw_parts(ptr, len),
+    };
+
+    let mut output = Vec::new();
+    let result = process_csv_bytes(bytes, &ProcessorConfig::default())
+        .and_then(|report| write_client_info_to_writer(&report.client_info, &mut output));
+    let status = match result {
+        Ok(()) => FFI_OK,
+        Err(e) => {
+            output = e.to_string().into_bytes();
+            FFI_PROCESSING_ERROR
+        }
+    };
+
+    let output = output.into_boxed_slice();
+    *out_len = output.len();
+    *out_ptr = Box::into_raw(output).cast();
+    status
+}
+
+/// Releases a buffer returned by `process_csv_bytes_ffi`.
+///
+/// # Safety
+///
+/// `ptr` and `len` must be a buffer returned by `process_csv_bytes_ffi` that was not released
+/// yet, or `ptr` may be null.
+#[no_mangle]
+pub unsafe extern "C" fn process_csv_bytes_free(ptr: *mut u8, len: usize) {
+    if !ptr.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len)));
+    }
+}
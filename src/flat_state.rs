@@ -0,0 +1,56 @@
+use crate::{Amount, DisputeStage, Error, TransactionProcessor};
+use serde::Serialize;
+use std::io;
+
+// a transaction joined with the balances of its client, for sql style reporting
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlatStateRow {
+    pub tx_id: u32,
+    pub client_id: u16,
+    pub tx_amount: Amount,
+    pub tx_dispute_stage: DisputeStage,
+    pub client_available: Amount,
+    pub client_held: Amount,
+    pub client_total: Amount,
+    pub client_locked: bool,
+}
+
+pub type FlatProcessorState = Vec<FlatStateRow>;
+
+// one row per transaction sorted by tx id, clients without transactions are not included
+pub fn flatten_processor_state(processor: &TransactionProcessor) -> FlatProcessorState {
+    let client_info = processor.client_info();
+    let mut rows: FlatProcessorState = processor
+        .tx_map()
+        .iter()
+        .filter_map(|(tx_id, tx)| {
+            let client = client_info.get(&tx.client)?;
+            Some(FlatStateRow {
+                tx_id: *tx_id,
+                client_id: tx.client,
+                tx_amount: tx.amount,
+                tx_dispute_stage: tx.dispute_stage,
+                client_available: client.available,
+                client_held: client.held,
+                client_total: client.total,
+                client_locked: client.locked,
+            })
+        })
+        .collect();
+    rows.sort_unstable_by_key(|row| row.tx_id);
+    rows
+}
+
+pub fn write_flat_state_csv<W: io::Write>(
+    state: &FlatProcessorState,
+    writer: W,
+) -> Result<(), Error> {
+    // the header is written from the field names of FlatStateRow along with the first row, so an
+    // empty state writes nothing
+    let mut wtr = csv::Writer::from_writer(writer);
+    for row in state {
+        wtr.serialize(row)?;
+    }
+    wtr.flush()?;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use crate::{ClientInfo, HashAlgorithm, Transaction};
+use std::collections::hash_map::{DefaultHasher, RandomState};
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+
+// the maps holding the clients and transactions while processing, hashed with the algorithm of
+// ProcessorConfig::hash_algorithm
+pub type ClientMap = HashMap<u16, ClientInfo, MapHasher>;
+pub type TxMap = HashMap<u32, Transaction, MapHasher>;
+
+// builds the hasher of the selected algorithm, so that a single map type can use any of them
+#[derive(Clone)]
+pub enum MapHasher {
+    Default(RandomState),
+    #[cfg(feature = "fxhash")]
+    Fx(rustc_hash::FxBuildHasher),
+    AHash(ahash::RandomState),
+}
+
+impl MapHasher {
+    pub fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Default => MapHasher::Default(RandomState::new()),
+            #[cfg(feature = "fxhash")]
+            HashAlgorithm::Fx => MapHasher::Fx(rustc_hash::FxBuildHasher),
+            HashAlgorithm::AHash => MapHasher::AHash(ahash::RandomState::new()),
+        }
+    }
+}
+
+impl Default for MapHasher {
+    fn default() -> Self {
+        MapHasher::new(HashAlgorithm::default())
+    }
+}
+
+impl BuildHasher for MapHasher {
+    type Hasher = MapHasherState;
+
+    fn build_hasher(&self) -> MapHasherState {
+        match self {
+            MapHasher::Default(state) => MapHasherState::Default(state.build_hasher()),
+            #[cfg(feature = "fxhash")]
+            MapHasher::Fx(state) => MapHasherState::Fx(state.build_hasher()),
+            MapHasher::AHash(state) => MapHasherState::AHash(state.build_hasher()),
+        }
+    }
+}
+
+pub enum MapHasherState {
+    Default(DefaultHasher),
+    #[cfg(feature = "fxhash")]
+    Fx(rustc_hash::FxHasher),
+    AHash(ahash::AHasher),
+}
+
+// forward to the selected hasher, keeping its fast paths for integer keys
+macro_rules! dispatch {
+    ($self:ident, $hasher:ident => $call:expr) => {
+        match $self {
+            MapHasherState::Default($hasher) => $call,
+            #[cfg(feature = "fxhash")]
+            MapHasherState::Fx($hasher) => $call,
+            MapHasherState::AHash($hasher) => $call,
+        }
+    };
+}
+
+impl Hasher for MapHasherState {
+    fn finish(&self) -> u64 {
+        dispatch!(self, hasher => hasher.finish())
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        dispatch!(self, hasher => hasher.write(bytes))
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        dispatch!(self, hasher => hasher.write_u16(i))
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        dispatch!(self, hasher => hasher.write_u32(i))
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        dispatch!(self, hasher => hasher.write_u64(i))
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        dispatch!(self, hasher => hasher.write_usize(i))
+    }
+}
@@ -0,0 +1,27 @@
+use crate::{
+    process_transactions_from_reader, validate_processor_config, ClientReport, Error,
+    ProcessorConfig,
+};
+use std::io;
+
+// download a csv file over http and process it, streaming the response body straight into the
+// csv reader. with the `compression` feature gzip encoded responses are decoded transparently
+pub fn process_transactions_from_url(
+    url: &str,
+    config: ProcessorConfig,
+) -> Result<ClientReport, Error> {
+    validate_processor_config(&config).map_err(Error::InvalidConfig)?;
+
+    let response = match ureq::get(url).call() {
+        Ok(response) => response,
+        Err(ureq::Error::StatusCode(status)) => {
+            return Err(Error::ReadError(io::Error::other(format!(
+                "Unexpected HTTP status {} while fetching {}",
+                status, url
+            ))))
+        }
+        Err(e) => return Err(Error::ReadError(io::Error::other(e))),
+    };
+
+    process_transactions_from_reader(response.into_body().into_reader(), &config)
+}
@@ -0,0 +1,1232 @@
+use csv::{ReaderBuilder, Trim};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io;
+use std::io::{BufRead, BufReader};
+use std::ops::Add;
+use std::path::{Path, PathBuf};
+use thiserror::Error as ThisError;
+
+#[cfg(feature = "tokio")]
+mod async_processing;
+#[cfg(feature = "checksum")]
+mod checksum;
+mod config;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod flat_state;
+mod hashing;
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "metrics")]
+mod metrics_registry;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod processor;
+#[cfg(feature = "profiling")]
+mod profiling;
+mod state;
+mod transaction_log;
+mod wallet;
+#[cfg(feature = "watch")]
+mod watch;
+
+pub use config::{
+    validate_processor_config, BelowZeroPolicy, ChargebackHook, ConfigError,
+    DuplicateTransactionPolicy, ErrorPolicy, HashAlgorithm, LoggingChargebackNotifier,
+    NewClientHook, ProcessorConfig,
+};
+pub use flat_state::{
+    flatten_processor_state, write_flat_state_csv, FlatProcessorState, FlatStateRow,
+};
+pub use hashing::{ClientMap, MapHasher, MapHasherState, TxMap};
+pub use processor::{
+    account_closure, amount_histogram_for_client, apply_dispute_with_partial_amount,
+    client_info_percentile_rank, compute_chargeback_exposure_by_client, compute_expected_available,
+    compute_total_chargeback_exposure, exceeds_precision, merge_tx_maps, process_transactions,
+    rebalance_held_from_tx_map, summarize_dispute_outcomes, transaction_amount_histogram,
+    TransactionProcessor,
+};
+
+#[cfg(feature = "futures")]
+pub use async_processing::process_stream;
+#[cfg(feature = "tokio")]
+pub use async_processing::process_transactions_async;
+#[cfg(feature = "ffi")]
+pub use ffi::{
+    process_csv_bytes_ffi, process_csv_bytes_free, FFI_NULL_POINTER, FFI_OK, FFI_PROCESSING_ERROR,
+};
+#[cfg(feature = "http")]
+pub use http::process_transactions_from_url;
+#[cfg(feature = "metrics")]
+pub use metrics_registry::{process_transactions_with_metrics_registry, MetricsRegistry};
+#[cfg(feature = "rayon")]
+pub use parallel::{process_transactions_parallel, shard_by_client};
+#[cfg(feature = "profiling")]
+pub use profiling::{process_transactions_timing_breakdown, TimingBreakdown};
+use state::save_checkpoint;
+pub use state::{
+    export_client_info_delta, find_balance_discrepancy, load_checkpoint, load_state, save_state,
+    BalanceDiscrepancy, Checkpoint, ClientInfoDelta, EngineState,
+};
+pub use transaction_log::{
+    transaction_frequency_by_client, transaction_frequency_by_type, TransactionLog,
+};
+pub use wallet::{process_transactions_with_wallet_check, NullWalletValidator, WalletValidator};
+#[cfg(feature = "watch")]
+pub use watch::{watch_file, AppendedRecords};
+
+// every balance and transaction amount, swapping this alias changes the numeric type of the
+// whole crate. rust_decimal always keeps a 96 bit mantissa with up to 28 decimal places, the
+// decimal128 feature switches it to its legacy arithmetic instead of widening it
+pub type Amount = Decimal;
+
+#[derive(Debug, Deserialize)]
+struct TransactionEntry<'a> {
+    #[serde(rename = "type")]
+    tx_type: &'a [u8],
+    client: u16,
+    tx: u32,
+    amount: Option<Amount>,
+    // the client receiving the funds of a transfer
+    target: Option<u16>,
+}
+
+// a transaction record that does not borrow from the csv reader, for callers that build or
+// buffer records themselves
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionEntryOwned {
+    pub tx_type: TransactionType,
+    pub client: u16,
+    pub tx: u32,
+    pub amount: Option<Amount>,
+    pub target: Option<u16>,
+}
+
+impl<'a> From<&'a TransactionEntryOwned> for TransactionEntry<'a> {
+    fn from(entry: &'a TransactionEntryOwned) -> Self {
+        TransactionEntry {
+            tx_type: entry.tx_type.as_bytes(),
+            client: entry.client,
+            tx: entry.tx,
+            amount: entry.amount,
+            target: entry.target,
+        }
+    }
+}
+
+// decimals are (de)serialized as strings so that the state saved with bincode, which cannot
+// deserialize self describing formats, can be loaded back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClientInfo {
+    #[serde(with = "rust_decimal::serde::str")]
+    pub available: Amount,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub held: Amount,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub total: Amount,
+    pub locked: bool,
+    // set when the account was closed by account_closure
+    pub closure_reason: Option<ClosureReason>,
+}
+
+impl ClientInfo {
+    pub(crate) fn empty() -> Self {
+        ClientInfo {
+            available: dec!(0.0),
+            held: dec!(0.0),
+            total: dec!(0.0),
+            locked: false,
+            closure_reason: None,
+        }
+    }
+}
+
+// one of the balances of a client, see client_info_percentile_rank
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceField {
+    Available,
+    Held,
+    Total,
+}
+
+impl BalanceField {
+    pub fn of(&self, info: &ClientInfo) -> Amount {
+        match self {
+            BalanceField::Available => info.available,
+            BalanceField::Held => info.held,
+            BalanceField::Total => info.total,
+        }
+    }
+}
+
+// why an account was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClosureReason {
+    // the client asked for the account to be closed, only once every fund was withdrawn
+    CustomerRequest,
+    Fraud,
+    Regulatory,
+    Inactivity,
+}
+
+impl ClosureReason {
+    pub fn requires_zero_balance(&self) -> bool {
+        matches!(self, ClosureReason::CustomerRequest)
+    }
+}
+
+// the outcome of account_closure
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountClosureResult {
+    pub client_id: u16,
+    pub final_balance: Amount,
+    // whether transactions of the client could still have been disputed, which the lock of the
+    // closed account now prevents
+    pub open_disputes_blocked: bool,
+}
+
+// a single line for log messages
+impl fmt::Display for ClientInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "available={} held={} total={} locked={}",
+            self.available, self.held, self.total, self.locked
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TransactionType {
+    Deposit,
+    Withdrawal,
+    Transfer,
+    Dispute,
+    Resolve,
+    Chargeback,
+    Fee,
+    Reverse,
+}
+
+impl TransactionType {
+    pub fn from_bytes(tx_type: &[u8]) -> Option<Self> {
+        match tx_type {
+            b"deposit" => Some(TransactionType::Deposit),
+            b"withdrawal" => Some(TransactionType::Withdrawal),
+            b"transfer" => Some(TransactionType::Transfer),
+            b"dispute" => Some(TransactionType::Dispute),
+            b"resolve" => Some(TransactionType::Resolve),
+            b"chargeback" => Some(TransactionType::Chargeback),
+            b"fee" => Some(TransactionType::Fee),
+            b"reverse" => Some(TransactionType::Reverse),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            TransactionType::Deposit => b"deposit",
+            TransactionType::Withdrawal => b"withdrawal",
+            TransactionType::Transfer => b"transfer",
+            TransactionType::Dispute => b"dispute",
+            TransactionType::Resolve => b"resolve",
+            TransactionType::Chargeback => b"chargeback",
+            TransactionType::Fee => b"fee",
+            TransactionType::Reverse => b"reverse",
+        }
+    }
+}
+
+// create an enum for the different dispute stages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisputeStage {
+    None,
+    Open,
+    // the disputed amount is above max_dispute_amount, nothing is held while it is reviewed
+    // outside of the processor. the outcome of the review arrives as a resolve, which dismisses
+    // the dispute without moving funds, or as a chargeback, which takes the disputed amount from
+    // the available funds
+    PendingReview,
+    Resolved,
+    ChargeBack,
+    // the deposit or withdrawal was cancelled, it cannot be disputed anymore
+    Reversed,
+}
+
+// create a struct called transaction
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transaction {
+    // the key of the transaction in tx_map
+    pub tx_id: u32,
+    // deposit, withdrawal or transfer
+    pub tx_type: TransactionType,
+    pub client: u16,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub amount: Amount,
+    // the part of the amount held while the transaction is disputed
+    #[serde(with = "rust_decimal::serde::str")]
+    pub disputed_amount: Amount,
+    pub dispute_stage: DisputeStage,
+    // the client that received the funds when the transaction is a transfer
+    pub counterparty: Option<u16>,
+}
+
+impl fmt::Display for Transaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tx={} client={} amount={} stage={:?}",
+            self.tx_id, self.client, self.amount, self.dispute_stage
+        )
+    }
+}
+
+// the transaction id is unique, so it is the only part of the identity of a transaction
+impl Hash for Transaction {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tx_id.hash(state);
+    }
+}
+
+// what happened to a client's funds as the result of a single transaction
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProcessingEvent {
+    Deposit {
+        client: u16,
+        tx: u32,
+        amount: Amount,
+    },
+    Withdrawal {
+        client: u16,
+        tx: u32,
+        amount: Amount,
+    },
+    DisputeOpened {
+        client: u16,
+        tx: u32,
+        amount: Amount,
+    },
+    // nothing is held, see DisputeStage::PendingReview
+    DisputePendingReview {
+        client: u16,
+        tx: u32,
+        amount: Amount,
+    },
+    // amount is the part of the held funds that was released, zero for a pending review
+    Resolved {
+        client: u16,
+        tx: u32,
+        amount: Amount,
+    },
+    ChargeBack {
+        client: u16,
+        tx: u32,
+        amount: Amount,
+    },
+    Transfer {
+        client: u16,
+        tx: u32,
+        amount: Amount,
+        target: u16,
+    },
+    Fee {
+        client: u16,
+        tx: u32,
+        amount: Amount,
+    },
+    Reversed {
+        client: u16,
+        tx: u32,
+        amount: Amount,
+    },
+}
+
+// an operation on a client's account, in the order it was applied. written as json lines by
+// write_event_log_to_writer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AccountEvent {
+    Deposit {
+        client: u16,
+        tx: u32,
+        amount: Amount,
+    },
+    Withdrawal {
+        client: u16,
+        tx: u32,
+        amount: Amount,
+    },
+    Transfer {
+        client: u16,
+        tx: u32,
+        amount: Amount,
+        target: u16,
+    },
+    Fee {
+        client: u16,
+        tx: u32,
+        amount: Amount,
+    },
+    Reversed {
+        client: u16,
+        tx: u32,
+    },
+    DisputeOpened {
+        client: u16,
+        tx: u32,
+    },
+    DisputePendingReview {
+        client: u16,
+        tx: u32,
+    },
+    Resolved {
+        client: u16,
+        tx: u32,
+    },
+    ChargeBack {
+        client: u16,
+        tx: u32,
+    },
+    AccountLocked {
+        client: u16,
+    },
+}
+
+#[derive(Debug, ThisError)]
+pub enum Error {
+    #[error("Error reading transaction file: {0:?}")]
+    ReadError(#[from] io::Error),
+    #[error("Error parsing transaction file: {0:?}")]
+    ParseError(#[from] csv::Error),
+    #[error("Unexpected error while processing the transaction: {0:?}")]
+    UnexpectedError(String),
+    #[error("Invalid dispute amount {amount} for transaction {tx}")]
+    InvalidDisputeAmount { tx: u32, amount: Amount },
+    #[error("Invalid processor configuration: {0:?}")]
+    InvalidConfig(Vec<ConfigError>),
+    #[error("Amount {amount} for transaction {tx} has more than {max_dp} decimal places")]
+    PrecisionExceeded {
+        tx: u32,
+        amount: Amount,
+        max_dp: u32,
+    },
+    #[error("Client {client} has {available} available but {requested} was requested")]
+    InsufficientFunds {
+        client: u16,
+        available: Amount,
+        requested: Amount,
+    },
+    #[error("Record rejected: {0}")]
+    Rejected(RecordError),
+    #[error("Error reading or writing the processor state: {0:?}")]
+    StateError(#[from] bincode::Error),
+    #[error("Account of client {client} cannot be closed with {count} open disputes")]
+    OpenDisputes { client: u16, count: usize },
+    #[error("Account of client {client} cannot be closed with held {held} and total {total}")]
+    AccountNotSettled {
+        client: u16,
+        held: Amount,
+        total: Amount,
+    },
+    #[error("Available funds of client {client} dropped to {amount}")]
+    NegativeBalance { client: u16, amount: Amount },
+    #[error("Checksum of row {row} is {computed} but {expected} was expected")]
+    ChecksumMismatch {
+        row: u64,
+        expected: String,
+        computed: String,
+    },
+    #[error("Transaction {tx_id} is in both merged maps: {a_entry} and {b_entry}")]
+    ConflictingTransaction {
+        tx_id: u32,
+        a_entry: Transaction,
+        b_entry: Transaction,
+    },
+}
+
+// why a record was not applied
+#[derive(Debug, Clone, PartialEq, ThisError)]
+pub enum SkipReason {
+    #[error("amount {amount} has more than {max_dp} decimal places")]
+    PrecisionExceeded { amount: Amount, max_dp: u32 },
+    #[error("insufficient funds, {available} available but {requested} requested")]
+    InsufficientFunds {
+        available: Amount,
+        requested: Amount,
+    },
+    #[error("balance cap exceeded, {accepted} accepted and {rejected} rejected")]
+    BalanceCapExceeded { accepted: Amount, rejected: Amount },
+    #[error("withdrawal {rows_since} rows after the previous one, cooldown is {cooldown} rows")]
+    WithdrawalCooldown { rows_since: u64, cooldown: u64 },
+    #[error("transactions of type {tx_type:?} cannot be disputed")]
+    DisputeNotAllowedForType { tx_type: TransactionType },
+    #[error("new client rejected by the on_new_client hook")]
+    NewClientRejected,
+    #[error("client {client} rejected by the wallet validator")]
+    InvalidWallet { client: u16 },
+    #[error("transaction belongs to client {tx_client}")]
+    ClientMismatch { tx_client: u16 },
+    #[error("initial deposit {amount} is below the minimum of {minimum}")]
+    BelowMinimumInitialDeposit { amount: Amount, minimum: Amount },
+    #[error("exact duplicate of an earlier record")]
+    DuplicateRecord,
+    #[error("amount is missing")]
+    MissingAmount,
+    #[error("dispute amount {amount} is not within the transaction amount {tx_amount}")]
+    InvalidDisputeAmount { amount: Amount, tx_amount: Amount },
+    #[error("amount {amount} is not positive")]
+    NonPositiveAmount { amount: Amount },
+    #[error("merchant {merchant} cannot pay the chargeback fee of {fee}, {available} available")]
+    MerchantFeeFailed {
+        merchant: u16,
+        available: Amount,
+        fee: Amount,
+    },
+}
+
+// a record that was skipped while processing in lenient mode
+#[derive(Debug, Clone, PartialEq, ThisError)]
+#[error("row {row}, client {client}, tx {tx}: {reason}")]
+pub struct RecordError {
+    pub row: u64,
+    pub client: u16,
+    pub tx: u32,
+    pub reason: SkipReason,
+}
+
+impl From<RecordError> for Error {
+    fn from(record_error: RecordError) -> Self {
+        match record_error.reason {
+            SkipReason::PrecisionExceeded { amount, max_dp } => Error::PrecisionExceeded {
+                tx: record_error.tx,
+                amount,
+                max_dp,
+            },
+            SkipReason::InsufficientFunds {
+                available,
+                requested,
+            } => Error::InsufficientFunds {
+                client: record_error.client,
+                available,
+                requested,
+            },
+            _ => Error::Rejected(record_error),
+        }
+    }
+}
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+// the maximum number of decimal places accepted for an amount
+pub const MAX_DECIMAL_PLACES: u32 = 4;
+
+// the outcome of processing a file without applying it anywhere
+#[derive(Debug)]
+pub struct DryRunReport {
+    // every row that could not be parsed or applied, see process_transactions_returning_partial_on_error
+    pub validation_errors: Vec<(u64, Error)>,
+    pub expected_report: ClientReport,
+    // the number of records of each type that were read
+    pub transaction_counts: HashMap<TransactionType, u64>,
+    // clients that would end up locked, sorted by id
+    pub would_lock_accounts: Vec<u16>,
+}
+
+// what applying a batch of records would do, see simulate_batch_outcome
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SimulationResult {
+    // sorted by id
+    pub would_create_clients: Vec<u16>,
+    pub would_lock_clients: Vec<u16>,
+    pub would_affect_balances: HashMap<u16, BalanceDelta>,
+    pub expected_chargeback_count: u32,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BalanceDelta {
+    pub available_change: Amount,
+    pub held_change: Amount,
+    pub total_change: Amount,
+}
+
+// how the disputes of a tx_map ended, see summarize_dispute_outcomes. disputes pending review
+// count as still open, and the rates are fractions of total_disputed, zero without disputes
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DisputeOutcomeSummary {
+    pub total_disputed: u32,
+    pub resolved: u32,
+    pub charged_back: u32,
+    pub still_open: u32,
+    pub resolution_rate: Amount,
+    pub chargeback_rate: Amount,
+    pub avg_dispute_amount: Amount,
+    pub max_dispute_amount: Amount,
+}
+
+// where a page written by write_client_info_paginated lies among every client
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaginationInfo {
+    pub page: usize,
+    pub page_size: usize,
+    pub total_clients: usize,
+    pub total_pages: usize,
+    pub has_next: bool,
+    pub has_prev: bool,
+}
+
+// a client whose balances do not add up, see TransactionProcessor::check_held_balance_consistency
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeldBalanceDiscrepancy {
+    pub client: u16,
+    pub expected_available: Amount,
+    pub available: Amount,
+    pub expected_held: Amount,
+    pub held: Amount,
+}
+
+// the final state of every client seen while processing the transactions
+#[derive(Debug, Default)]
+pub struct ClientReport {
+    pub client_info: HashMap<u16, ClientInfo>,
+    // every accepted deposit, withdrawal and transfer with its final dispute stage
+    pub tx_map: HashMap<u32, Transaction>,
+    // records that were skipped in lenient mode
+    pub skipped: Vec<RecordError>,
+    pub stats: ProcessorStats,
+    pub client_stats: HashMap<u16, ClientStats>,
+    // every operation on the accounts when ProcessorConfig::record_event_log is set
+    pub event_log: Option<Vec<AccountEvent>>,
+}
+
+// the activity of a single client, for fraud analysis without reprocessing the input. the volumes
+// cover every movement of funds, so total_deposited includes incoming transfers and
+// total_withdrawn includes outgoing transfers, fees and chargebacks
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ClientStats {
+    pub deposit_count: u32,
+    pub withdrawal_count: u32,
+    pub total_deposited: Amount,
+    pub total_withdrawn: Amount,
+}
+
+// the number of records that were applied of each type
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessorStats {
+    pub deposits: u64,
+    pub withdrawals: u64,
+    pub transfers: u64,
+    pub disputes: u64,
+    pub resolves: u64,
+    pub chargebacks: u64,
+    pub reversals: u64,
+    // records skipped in lenient mode, including rejected fees
+    pub skipped: u64,
+    pub fees_applied: u64,
+    pub fees_rejected: u64,
+    // disputes above max_dispute_amount, which are not counted in disputes
+    pub disputes_pending_review: u64,
+    // records that left the available funds of their client below zero, see BelowZeroPolicy
+    pub negative_balance_count: u64,
+}
+
+// accumulate the stats of several runs
+impl Add for ProcessorStats {
+    type Output = ProcessorStats;
+
+    fn add(self, other: ProcessorStats) -> ProcessorStats {
+        ProcessorStats {
+            deposits: self.deposits + other.deposits,
+            withdrawals: self.withdrawals + other.withdrawals,
+            transfers: self.transfers + other.transfers,
+            disputes: self.disputes + other.disputes,
+            resolves: self.resolves + other.resolves,
+            chargebacks: self.chargebacks + other.chargebacks,
+            reversals: self.reversals + other.reversals,
+            skipped: self.skipped + other.skipped,
+            fees_applied: self.fees_applied + other.fees_applied,
+            fees_rejected: self.fees_rejected + other.fees_rejected,
+            disputes_pending_review: self.disputes_pending_review + other.disputes_pending_review,
+            negative_balance_count: self.negative_balance_count + other.negative_balance_count,
+        }
+    }
+}
+
+// the canonical header line of an input file. the columns are
+// - type: one of deposit, withdrawal, transfer, dispute, resolve, chargeback, fee or reverse
+// - client: the client id, an unsigned 16 bit integer
+// - tx: the transaction id, an unsigned 32 bit integer unique per deposit, withdrawal and transfer
+// - amount: a decimal with at most four decimal places, may be left empty for disputes,
+//   resolutions and chargebacks
+// a file holding transfers adds a fifth column, which is only read for transfers
+// - target: the id of the client receiving the transfer
+pub const CSV_HEADER: &str = "type,client,tx,amount";
+
+// columns accepted by tooling producing input files, they are not used while processing. the
+// `target` column is not one of them, see CSV_HEADER
+// - description: free text describing the transaction
+// - external_id: the id of the transaction in the upstream system
+// - currency: the iso 4217 code of the amount
+pub const CSV_OPTIONAL_COLUMNS: &str = "description,external_id,currency";
+
+pub fn generate_csv_template() -> String {
+    format!("{}\n", CSV_HEADER)
+}
+
+pub fn generate_csv_template_with_optional_columns() -> String {
+    format!("{},{}\n", CSV_HEADER, CSV_OPTIONAL_COLUMNS)
+}
+
+fn reader_builder() -> ReaderBuilder {
+    let mut builder = ReaderBuilder::new();
+    builder.trim(Trim::All).flexible(true); // to allow amount to be skipped in case of disputes, resolutions and chargebacks
+
+    // export scripts add comment lines. blank lines are skipped by the reader itself, which also
+    // accepts windows line endings by default
+    builder.comment(Some(b'#'));
+    builder
+}
+
+// a line holding only whitespace or empty fields, which is read as a record with empty fields
+fn is_blank_record(record: &csv::ByteRecord) -> bool {
+    record.iter().all(|field| field.is_empty())
+}
+
+pub fn process_transactions_from_reader<R>(
+    reader: R,
+    config: &ProcessorConfig,
+) -> Result<ClientReport, Error>
+where
+    R: io::Read,
+{
+    let mut rdr = reader_builder().from_reader(reader);
+
+    // Reading into a ByteRecord instead of a StringRecord for best performance
+    let raw_record = csv::ByteRecord::new();
+
+    let mut processor = TransactionProcessor::new(config);
+    process_transactions(&mut rdr, raw_record, &mut processor)?;
+    Ok(processor.into_report())
+}
+
+pub fn process_transactions_from_str(
+    input: &str,
+    config: &ProcessorConfig,
+) -> Result<ClientReport, Error> {
+    process_transactions_from_reader(input.as_bytes(), config)
+}
+
+// the csv held in memory, for callers that already read it, like the ffi
+pub fn process_csv_bytes(bytes: &[u8], config: &ProcessorConfig) -> Result<ClientReport, Error> {
+    process_transactions_from_reader(io::Cursor::new(bytes), config)
+}
+
+pub fn process_transactions_from_path(
+    path: &str,
+    config: &ProcessorConfig,
+) -> Result<ClientReport, Error> {
+    validate_processor_config(config).map_err(Error::InvalidConfig)?;
+
+    let mut processor = TransactionProcessor::new(config);
+    process_transactions_from_path_into(path, &mut processor)?;
+    Ok(processor.into_report())
+}
+
+// apply a file on top of the state already held by the processor, like one restored with
+// load_state
+pub fn process_transactions_from_path_into(
+    path: &str,
+    processor: &mut TransactionProcessor,
+) -> Result<(), Error> {
+    process_file_into(Path::new(path), processor)
+}
+
+// process a file, calling the observer with every change applied to the funds of a client
+pub fn process_transactions_with_observer<F>(
+    path: PathBuf,
+    config: ProcessorConfig,
+    observer: F,
+) -> Result<ClientReport, Error>
+where
+    F: FnMut(&ProcessingEvent),
+{
+    validate_processor_config(&config).map_err(Error::InvalidConfig)?;
+
+    let mut processor = TransactionProcessor::new(&config);
+    processor.set_observer(observer);
+    process_file_into(&path, &mut processor)?;
+    Ok(processor.into_report())
+}
+
+// like process_transactions_with_observer, returning the events in the order they happened
+pub fn process_transactions_collecting_events(
+    path: PathBuf,
+    config: ProcessorConfig,
+) -> Result<(ClientReport, Vec<ProcessingEvent>), Error> {
+    let mut events = Vec::new();
+    let report = process_transactions_with_observer(path, config, |event| {
+        events.push(event.clone());
+    })?;
+    Ok((report, events))
+}
+
+fn process_file_into(path: &Path, processor: &mut TransactionProcessor) -> Result<(), Error> {
+    let reader = open_transaction_file(path)?;
+    processor.set_input_len(reader.get_ref().metadata()?.len());
+
+    let mut rdr = reader_builder().from_reader(reader);
+    process_transactions(&mut rdr, csv::ByteRecord::new(), processor)
+}
+
+// process a file that may have been partly processed by an earlier run. with a checkpoint from
+// that run, its state is restored and its rows are skipped. the checkpoint is written again once
+// the file is done or when an error stops the processing, in which case the failing row is
+// applied again on the next run. the filter of skip_duplicate_records starts empty on every run
+pub fn process_transactions_resumable(
+    path: PathBuf,
+    checkpoint_path: Option<PathBuf>,
+    config: ProcessorConfig,
+) -> Result<ClientReport, Error> {
+    validate_processor_config(&config).map_err(Error::InvalidConfig)?;
+
+    let checkpoint = match &checkpoint_path {
+        Some(checkpoint_path) if checkpoint_path.exists() => {
+            Some(load_checkpoint(checkpoint_path)?)
+        }
+        _ => None,
+    };
+    let (row, mut processor) = match checkpoint {
+        Some(checkpoint) => (
+            checkpoint.row,
+            TransactionProcessor::with_state(&config, checkpoint.client_info, checkpoint.tx_map),
+        ),
+        None => (0, TransactionProcessor::new(&config)),
+    };
+    processor.set_row(row);
+
+    let mut completed = row;
+    let result = open_transaction_file(&path).and_then(|reader| {
+        let mut rdr = reader_builder().from_reader(reader);
+        apply_rows_after(&mut rdr, &mut processor, row, &mut completed)
+    });
+
+    if let Some(checkpoint_path) = &checkpoint_path {
+        let saved = save_checkpoint(
+            completed,
+            processor.client_info(),
+            processor.tx_map(),
+            checkpoint_path,
+        );
+        match (&result, saved) {
+            (Ok(()), saved) => saved?,
+            (Err(_), Err(e)) => log::warn!("Could not write the checkpoint: {}", e),
+            (Err(_), Ok(())) => {}
+        }
+    }
+    result?;
+    Ok(processor.into_report())
+}
+
+// apply the records after the first `row` ones, counting every applied record in `completed`
+fn apply_rows_after<R: io::Read>(
+    rdr: &mut csv::Reader<R>,
+    processor: &mut TransactionProcessor,
+    row: u64,
+    completed: &mut u64,
+) -> Result<(), Error> {
+    let headers = rdr.byte_headers()?.clone();
+    let mut raw_record = csv::ByteRecord::new();
+    let mut skipped = 0;
+    while rdr.read_byte_record(&mut raw_record)? {
+        if is_blank_record(&raw_record) {
+            continue;
+        }
+        // a csv reader cannot seek to a row, so the rows of the earlier run are read again
+        if skipped < row {
+            skipped += 1;
+            continue;
+        }
+        processor.apply_byte_record(&raw_record, &headers)?;
+        *completed += 1;
+    }
+    Ok(())
+}
+
+// best effort processing that never fails, returning whatever could be computed along with the
+// row number of every error. errors that are not tied to a row, like a missing file, use row 0
+pub fn process_transactions_returning_partial_on_error(
+    path: PathBuf,
+    mut config: ProcessorConfig,
+) -> (ClientReport, Vec<(u64, Error)>) {
+    config.error_policy = ErrorPolicy::CollectErrors;
+    let (processor, fatal_error) = process_path_collecting_errors(&path, &config, false);
+    let (report, mut errors) = processor.into_report_with_errors();
+    errors.extend(fatal_error);
+    (report, errors)
+}
+
+// validate and process a whole file without failing, reporting what the result would be. nothing
+// is written to the audit trail and the hooks are not called
+pub fn process_transactions_dry_run_with_report(
+    path: PathBuf,
+    mut config: ProcessorConfig,
+) -> DryRunReport {
+    config.error_policy = ErrorPolicy::CollectErrors;
+    let (processor, fatal_error) = process_path_collecting_errors(&path, &config, true);
+    let transaction_counts = processor.transaction_counts().clone();
+    let (expected_report, mut validation_errors) = processor.into_report_with_errors();
+    validation_errors.extend(fatal_error);
+
+    let mut would_lock_accounts: Vec<u16> = expected_report
+        .client_info
+        .iter()
+        .filter(|(_, info)| info.locked)
+        .map(|(client, _)| *client)
+        .collect();
+    would_lock_accounts.sort_unstable();
+
+    DryRunReport {
+        validation_errors,
+        expected_report,
+        transaction_counts,
+        would_lock_accounts,
+    }
+}
+
+// apply the records to a throwaway processor for pre-authorization checks. every client starts
+// out empty, so each one the batch touches is created. like processing, the batch stops at the
+// first error that the error policy does not skip. nothing is written to the audit trail and the
+// hooks are not called
+pub fn simulate_batch_outcome(
+    entries: &[TransactionEntryOwned],
+    config: &ProcessorConfig,
+) -> SimulationResult {
+    let mut processor = TransactionProcessor::new(config);
+    processor.set_simulated();
+    for entry in entries {
+        if processor.apply_entry(entry).is_err() {
+            break;
+        }
+    }
+    let report = processor.into_report();
+
+    let mut result = SimulationResult {
+        expected_chargeback_count: report.stats.chargebacks as u32,
+        ..Default::default()
+    };
+    for (client, info) in report.client_info {
+        result.would_create_clients.push(client);
+        if info.locked {
+            result.would_lock_clients.push(client);
+        }
+        let delta = BalanceDelta {
+            available_change: info.available,
+            held_change: info.held,
+            total_change: info.total,
+        };
+        if delta != BalanceDelta::default() {
+            result.would_affect_balances.insert(client, delta);
+        }
+    }
+    result.would_create_clients.sort_unstable();
+    result.would_lock_clients.sort_unstable();
+    result
+}
+
+// process a file under ErrorPolicy::CollectErrors. a failure that stops the processing, like a
+// missing file, is returned separately and the processor keeps what was processed until then
+fn process_path_collecting_errors<'a>(
+    path: &Path,
+    config: &'a ProcessorConfig,
+    simulated: bool,
+) -> (TransactionProcessor<'a>, Option<(u64, Error)>) {
+    let mut processor = TransactionProcessor::new(config);
+    if simulated {
+        processor.set_simulated();
+    }
+    if let Err(errors) = validate_processor_config(config) {
+        return (processor, Some((0, Error::InvalidConfig(errors))));
+    }
+
+    let reader = match open_transaction_file(path) {
+        Ok(reader) => reader,
+        Err(e) => return (processor, Some((0, e))),
+    };
+    // the size is only needed for the progress percentage
+    if let Ok(metadata) = reader.get_ref().metadata() {
+        processor.set_input_len(metadata.len());
+    }
+    let mut rdr = reader_builder().from_reader(reader);
+
+    match process_transactions(&mut rdr, csv::ByteRecord::new(), &mut processor) {
+        Ok(()) => (processor, None),
+        Err(e) => {
+            let row = processor.rows();
+            (processor, Some((row, e)))
+        }
+    }
+}
+
+fn open_transaction_file(path: &Path) -> Result<BufReader<File>, Error> {
+    // create a reader for the csv file
+    let mut reader = BufReader::new(File::open(path)?);
+    strip_utf8_bom(&mut reader)?;
+    Ok(reader)
+}
+
+// windows tools like excel prefix csv exports with a utf-8 byte order mark, which would otherwise
+// end up as part of the first header name
+fn strip_utf8_bom<R: BufRead>(reader: &mut R) -> Result<(), Error> {
+    if reader.fill_buf()?.starts_with(UTF8_BOM) {
+        reader.consume(UTF8_BOM.len());
+    }
+    Ok(())
+}
+
+pub fn write_client_info<S: BuildHasher>(
+    client_info: &HashMap<u16, ClientInfo, S>,
+) -> Result<(), Error> {
+    write_client_info_to_writer(client_info, io::stdout())
+}
+
+// clients are sorted by id so that the output is the same on every run
+pub fn write_client_info_to_writer<W: io::Write, S: BuildHasher>(
+    client_info: &HashMap<u16, ClientInfo, S>,
+    writer: W,
+) -> Result<(), Error> {
+    write_clients_to_writer(client_info, |_| true, None, true, writer)
+}
+
+// write the clients of a single page, counting pages from 0 in the order of
+// write_client_info_to_writer. the header is only written on the first page, so the pages put
+// together give the same output as write_client_info_to_writer
+pub fn write_client_info_paginated<W: io::Write>(
+    client_info: &HashMap<u16, ClientInfo>,
+    writer: W,
+    page: usize,
+    page_size: usize,
+) -> Result<PaginationInfo, Error> {
+    if page_size == 0 {
+        return Err(Error::UnexpectedError("Page size must be positive".into()));
+    }
+
+    let mut clients: Vec<u16> = client_info.keys().copied().collect();
+    clients.sort_unstable();
+    // the page holds a contiguous range of the sorted ids
+    let start = page.saturating_mul(page_size).min(clients.len());
+    let end = start.saturating_add(page_size).min(clients.len());
+    let page_clients = &clients[start..end];
+    let (first, last) = match (page_clients.first(), page_clients.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => (1, 0),
+    };
+    write_clients_to_writer(
+        client_info,
+        |client| first <= client && client <= last,
+        None,
+        page == 0,
+        writer,
+    )?;
+
+    let total_pages = clients.len().div_ceil(page_size);
+    Ok(PaginationInfo {
+        page,
+        page_size,
+        total_clients: clients.len(),
+        total_pages,
+        has_next: page.saturating_add(1) < total_pages,
+        has_prev: page > 0,
+    })
+}
+
+// like write_client_info_to_writer, leaving out every client that is not in `clients`
+pub fn write_selected_client_info_to_writer<W: io::Write, S: BuildHasher>(
+    client_info: &HashMap<u16, ClientInfo, S>,
+    clients: &HashSet<u16>,
+    writer: W,
+) -> Result<(), Error> {
+    write_clients_to_writer(
+        client_info,
+        |client| clients.contains(&client),
+        None,
+        true,
+        writer,
+    )
+}
+
+// like write_client_info_to_writer with the activity of every client in extra columns, limited
+// to `clients` when given
+pub fn write_client_info_with_stats_to_writer<W: io::Write, S: BuildHasher>(
+    client_info: &HashMap<u16, ClientInfo, S>,
+    client_stats: &HashMap<u16, ClientStats>,
+    clients: Option<&HashSet<u16>>,
+    writer: W,
+) -> Result<(), Error> {
+    let include = |client| clients.is_none_or(|clients| clients.contains(&client));
+    write_clients_to_writer(client_info, include, Some(client_stats), true, writer)
+}
+
+fn write_clients_to_writer<W: io::Write, S: BuildHasher>(
+    client_info: &HashMap<u16, ClientInfo, S>,
+    include: impl Fn(u16) -> bool,
+    client_stats: Option<&HashMap<u16, ClientStats>>,
+    write_headers: bool,
+    writer: W,
+) -> Result<(), Error> {
+    let mut wtr = csv::Writer::from_writer(writer);
+    // write headers
+    let mut headers = vec!["client", "available", "held", "total", "locked"];
+    if client_stats.is_some() {
+        headers.extend([
+            "deposit_count",
+            "withdrawal_count",
+            "total_deposited",
+            "total_withdrawn",
+        ]);
+    }
+    if write_headers {
+        wtr.write_record(&headers)?;
+    }
+
+    let mut clients: Vec<(&u16, &ClientInfo)> = client_info.iter().collect();
+    clients.sort_unstable_by_key(|(client, _)| **client);
+    for (client, info) in clients.into_iter().filter(|(client, _)| include(**client)) {
+        let balances = (
+            client,
+            &info.available,
+            &info.held,
+            &info.total,
+            &info.locked,
+        );
+        match client_stats {
+            Some(client_stats) => {
+                let stats = client_stats.get(client).cloned().unwrap_or_default();
+                wtr.serialize((
+                    balances,
+                    stats.deposit_count,
+                    stats.withdrawal_count,
+                    stats.total_deposited,
+                    stats.total_withdrawn,
+                ))?;
+            }
+            None => wtr.serialize(balances)?,
+        }
+    }
+
+    // flush the writer
+    wtr.flush()?;
+    Ok(())
+}
+
+// write every transaction sorted by id, for offline reconciliation without replaying the input
+pub fn write_transaction_log_to_writer<W: io::Write>(
+    tx_map: &HashMap<u32, Transaction>,
+    writer: W,
+) -> Result<(), Error> {
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.write_record(["tx", "client", "amount", "dispute_stage"])?;
+
+    let mut tx_ids: Vec<&u32> = tx_map.keys().collect();
+    tx_ids.sort_unstable();
+    for tx_id in tx_ids {
+        let tx = &tx_map[tx_id];
+        wtr.serialize((tx_id, tx.client, &tx.amount, &tx.dispute_stage))?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+// write the clients sorted by id, or only the selected ones, along with the stats of the run as a
+// single json object
+pub fn write_client_info_json_to_writer<W: io::Write, S: BuildHasher>(
+    client_info: &HashMap<u16, ClientInfo, S>,
+    stats: &ProcessorStats,
+    selected: Option<&HashSet<u16>>,
+    mut writer: W,
+) -> Result<(), Error> {
+    #[derive(Serialize)]
+    struct JsonClient<'a> {
+        client: u16,
+        #[serde(flatten)]
+        info: &'a ClientInfo,
+    }
+
+    let mut clients: Vec<JsonClient> = client_info
+        .iter()
+        .filter(|(client, _)| selected.is_none_or(|selected| selected.contains(client)))
+        .map(|(client, info)| JsonClient {
+            client: *client,
+            info,
+        })
+        .collect();
+    clients.sort_unstable_by_key(|json_client| json_client.client);
+
+    let body = serde_json::json!({ "clients": clients, "stats": stats });
+    serde_json::to_writer(&mut writer, &body).map_err(io::Error::from)?;
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+    Ok(())
+}
+
+// write one json object per line for every event, in the order they were applied
+pub fn write_event_log_to_writer<W: io::Write>(
+    events: &[AccountEvent],
+    mut writer: W,
+) -> Result<(), Error> {
+    for event in events {
+        serde_json::to_writer(&mut writer, event).map_err(io::Error::from)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// write the stats as a single row under a header of their field names, so the stats of many runs
+// can be gathered in a spreadsheet
+pub fn export_summary_stats_csv<W: io::Write>(
+    stats: &ProcessorStats,
+    writer: W,
+) -> Result<(), Error> {
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.serialize(stats)?;
+    wtr.flush()?;
+    Ok(())
+}
+
+// read back the stats written by export_summary_stats_csv
+pub fn load_summary_stats_csv<R: io::Read>(reader: R) -> Result<ProcessorStats, Error> {
+    let mut rdr = ReaderBuilder::new().trim(Trim::All).from_reader(reader);
+    match rdr.deserialize().next() {
+        Some(stats) => Ok(stats?),
+        None => Err(Error::UnexpectedError("Missing summary stats row".into())),
+    }
+}
+
+// write the chargeback exposure of every client with open disputes sorted by client, followed by
+// the total exposure
+pub fn export_risk_summary<W: io::Write>(
+    tx_map: &HashMap<u32, Transaction>,
+    writer: W,
+) -> Result<(), Error> {
+    let mut wtr = csv::Writer::from_writer(writer);
+    wtr.write_record(["client", "exposure"])?;
+
+    let mut exposure: Vec<(u16, Amount)> = compute_chargeback_exposure_by_client(tx_map)
+        .into_iter()
+        .collect();
+    exposure.sort_unstable_by_key(|(client, _)| *client);
+    for (client, amount) in exposure {
+        wtr.serialize((client, amount))?;
+    }
+    wtr.serialize(("total", compute_total_chargeback_exposure(tx_map)))?;
+
+    wtr.flush()?;
+    Ok(())
+}
@@ -1,307 +1,244 @@
-use crate::Error::UnexpectedError;
-use csv::{ByteRecord, Reader, Trim};
-use rust_decimal::Decimal;
-use rust_decimal_macros::dec;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::{env, io};
-use thiserror::Error as ThisError;
-
-#[derive(Debug, Deserialize)]
-struct TransactionEntry<'a> {
-    #[serde(rename = "type")]
-    tx_type: &'a [u8],
-    client: u16,
-    tx: u32,
-    amount: Option<Decimal>,
-}
-
-#[derive(Debug, Serialize)]
-struct ClientInfo {
-    available: Decimal,
-    held: Decimal,
-    total: Decimal,
-    locked: bool,
-}
-
-// create an enum for the different dispute stages
-#[derive(PartialEq, Serialize)]
-enum DisputeStage {
-    None,
-    Open,
-    ChargeBack,
-}
-
-// create a struct called transaction
-#[derive(Serialize)]
-struct Transaction {
-    client: u16,
-    amount: Decimal,
-    dispute_stage: DisputeStage,
+use serde_json::json;
+use std::collections::HashSet;
+use std::fs::File;
+use std::path::Path;
+use std::{env, io, process};
+#[cfg(feature = "watch")]
+use transaction_processor::watch_file;
+use transaction_processor::{
+    load_state, process_transactions_from_path_into, rebalance_held_from_tx_map, save_state,
+    write_client_info, write_client_info_json_to_writer, write_client_info_with_stats_to_writer,
+    write_selected_client_info_to_writer, write_transaction_log_to_writer, Error, ProcessorConfig,
+    RecordError, TransactionProcessor,
+};
+
+// --watch is only listed when the feature that accepts it is enabled
+fn usage() -> String {
+    let watch = match cfg!(feature = "watch") {
+        true => " [--watch]",
+        false => "",
+    };
+    format!(
+        "Usage: transaction_processor <transactions.csv> [--audit <path>] \
+         [--load-state <path>] [--save-state <path>] [--client <id>]... [--verbose]{} \
+         [--format=csv|json]
+       transaction_processor --rebalance <state> [--save-state <path>]",
+        watch
+    )
 }
 
-#[derive(Debug, ThisError)]
-enum Error {
-    #[error("Error reading transaction file: {0:?}")]
-    ReadError(#[from] io::Error),
-    #[error("Error parsing transaction file: {0:?}")]
-    ParseError(#[from] csv::Error),
-    #[error("Unexpected error while processing the transaction: {0:?}")]
-    UnexpectedError(String),
+#[derive(Debug, Default)]
+struct Options {
+    file_path: String,
+    // where to write the transaction log for audits
+    audit_path: Option<String>,
+    // state of an earlier run to resume from, and where to save the state after this run
+    load_state_path: Option<String>,
+    save_state_path: Option<String>,
+    // only these clients are written to the output, every client when empty
+    clients: HashSet<u16>,
+    // recompute the held balances of a saved state instead of processing a file
+    rebalance_path: Option<String>,
+    // add the activity of every client to the output
+    verbose: bool,
+    // write the clients and the stats of the run as a json object instead of the csv
+    json: bool,
+    // keep processing the records appended to the file, writing the clients they change
+    #[cfg(feature = "watch")]
+    watch: bool,
 }
 
-fn process_transactions<R>(
-    rdr: &mut Reader<R>,
-    mut raw_record: ByteRecord,
-    client_info: &mut HashMap<u16, ClientInfo>,
-) -> Result<(), Error>
-where
-    R: io::Read,
-{
-    let mut tx_map: HashMap<u32, Transaction> = HashMap::new();
-    while rdr.read_byte_record(&mut raw_record)? {
-        let record: TransactionEntry = raw_record.deserialize(Some(rdr.byte_headers()?))?;
-
-        // if the client is locked, continue
-        if client_info.contains_key(&record.client) {
-            match client_info.get(&record.client) {
-                Some(client) => {
-                    if client.locked {
-                        continue;
-                    }
-                }
-                None => {
-                    return Err(UnexpectedError(format!(
-                        "Client id {} not found",
-                        record.client
-                    )))
-                }
-            };
-        }
-
-        match record.tx_type {
-            b"deposit" => {
-                if tx_map.contains_key(&record.tx) {
-                    continue;
-                }
-
-                // if record.amount is None, continue
-                if record.amount.is_none() {
-                    continue;
-                }
-
-                let amount_option: Option<Decimal> = record.amount.map(|amt: Decimal| {
-                    let client_funds = client_info.entry(record.client).or_insert(ClientInfo {
-                        available: dec!(0.0),
-                        held: dec!(0.0),
-                        total: dec!(0.0),
-                        locked: false,
-                    });
-                    client_funds.available += amt;
-                    client_funds.total += amt;
-                    amt
-                });
-
-                let amount = match amount_option {
-                    Some(amt) => amt,
-                    None => continue, // partner side error, ignore and continue to next transaction
-                };
-
-                tx_map.insert(
-                    record.tx,
-                    Transaction {
-                        client: record.client,
-                        amount,
-                        dispute_stage: DisputeStage::None,
-                    },
-                );
-            }
-            b"withdrawal" => {
-                // if amount is none or if the client id is something that have not been seen before, continue to next transaction
-                if record.amount.is_none() || !client_info.contains_key(&record.client) {
-                    continue;
-                }
-
-                let client_funds = match client_info.get_mut(&record.client) {
-                    Some(funds) => funds,
-                    None => {
-                        return Err(Error::UnexpectedError(format!(
-                            "Client id {} not found",
-                            record.client
-                        )))
-                    }
-                };
-
-                let amount_option: Option<Decimal> = record.amount.map(|amt| {
-                    // if there are enough available funds to withdraw, withdraw the amount
-                    if client_funds.available >= amt {
-                        client_funds.available -= amt;
-                        client_funds.total -= amt;
-                        amt
-                    } else {
-                        dec!(-1.0)
-                    }
-                });
-
-                let amount = match amount_option {
-                    Some(amt) => amt,
-                    None => continue, // partner side error, ignore and continue to next transaction
-                };
-
-                if amount >= dec!(0.0) {
-                    tx_map.insert(
-                        record.tx,
-                        Transaction {
-                            client: record.client,
-                            amount,
-                            dispute_stage: DisputeStage::None,
-                        },
-                    );
-                }
-            }
-            b"dispute" => {
-                if !tx_map.contains_key(&record.tx) || !client_info.contains_key(&record.client) {
-                    continue;
-                }
-
-                let tx = match tx_map.get_mut(&record.tx) {
-                    Some(tx) => tx,
-                    None => {
-                        return Err(Error::UnexpectedError(format!(
-                            "Transaction id {} not found",
-                            record.tx
-                        )))
-                    }
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut file_path = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--audit" | "--load-state" | "--save-state" | "--rebalance" => {
+                let path = match args.next() {
+                    Some(path) => path,
+                    None => return Err(format!("{} requires a path", arg)),
                 };
-
-                // if the client in tx does not match the client in the dispute or if dispute stage is not None, continue
-                if tx.client != record.client || tx.dispute_stage != DisputeStage::None {
-                    continue;
+                match arg.as_str() {
+                    "--audit" => options.audit_path = Some(path),
+                    "--load-state" => options.load_state_path = Some(path),
+                    "--rebalance" => options.rebalance_path = Some(path),
+                    _ => options.save_state_path = Some(path),
                 }
-
-                tx.dispute_stage = DisputeStage::Open;
-
-                let client_funds = match client_info.get_mut(&record.client) {
-                    Some(funds) => funds,
-                    None => continue, // partner side error, ignore and continue to next transaction
-                };
-
-                // decrease the available funds by the amount in the tx
-                client_funds.available -= tx.amount;
-                client_funds.held += tx.amount;
             }
-            b"resolve" => {
-                if !tx_map.contains_key(&record.tx) || !client_info.contains_key(&record.client) {
-                    continue;
-                }
-
-                let tx = match tx_map.get_mut(&record.tx) {
-                    Some(tx) => tx,
-                    None => {
-                        return Err(Error::UnexpectedError(format!(
-                            "Transaction id {} not found",
-                            record.tx
-                        )))
-                    }
-                };
-
-                // if the client in tx does not match the client in the dispute, continue
-                if tx.client != record.client || tx.dispute_stage != DisputeStage::Open {
-                    continue;
-                }
-
-                let client_funds = match client_info.get_mut(&record.client) {
-                    Some(funds) => funds,
-                    None => continue, // partner side error, ignore and continue to next transaction
-                };
-
-                client_funds.available += tx.amount;
-                client_funds.held -= tx.amount;
+            "--client" => {
+                let client = args
+                    .next()
+                    .and_then(|id| id.parse().ok())
+                    .ok_or("--client requires a client id")?;
+                options.clients.insert(client);
             }
-            b"chargeback" => {
-                if !tx_map.contains_key(&record.tx) || !client_info.contains_key(&record.client) {
-                    continue;
-                }
-
-                let tx = match tx_map.get_mut(&record.tx) {
-                    Some(tx) => tx,
-                    None => {
-                        return Err(Error::UnexpectedError(format!(
-                            "Transaction id {} not found",
-                            record.tx
-                        )))
-                    }
-                };
-
-                if tx.client != record.client || tx.dispute_stage != DisputeStage::Open {
-                    continue;
-                }
-
-                let client_funds = match client_info.get_mut(&record.client) {
-                    Some(funds) => funds,
-                    None => continue, // partner side error, ignore and continue to next transaction
-                };
+            "--verbose" => options.verbose = true,
+            "--format=csv" => options.json = false,
+            "--format=json" => options.json = true,
+            #[cfg(feature = "watch")]
+            "--watch" => options.watch = true,
+            _ if arg.starts_with("--") => return Err(format!("Unknown flag {}", arg)),
+            _ if file_path.is_none() => file_path = Some(arg),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
 
-                client_funds.total -= tx.amount;
-                client_funds.held -= tx.amount;
-                tx.dispute_stage = DisputeStage::ChargeBack;
+    match (file_path, &options.rebalance_path) {
+        (Some(file_path), None) => options.file_path = file_path,
+        (None, Some(_)) => {}
+        (Some(_), Some(_)) => return Err("--rebalance does not take a transactions file".into()),
+        (None, None) => return Err("Missing transactions file".into()),
+    }
+    if options.json && options.verbose {
+        return Err("--verbose cannot be used with --format=json".into());
+    }
+    // a watch never ends, so there is no final state to save or audit
+    #[cfg(feature = "watch")]
+    if options.watch && (options.save_state_path.is_some() || options.audit_path.is_some()) {
+        return Err("--watch cannot be used with --save-state or --audit".into());
+    }
+    #[cfg(feature = "watch")]
+    if options.watch && options.json {
+        return Err("--watch cannot be used with --format=json".into());
+    }
+    Ok(options)
+}
 
-                // lock the clients account
-                client_funds.locked = true;
-            }
-            _ => {
-                continue; // partner side error, ignore and continue to next transaction
-            }
+fn run(options: &Options) -> Result<(), Error> {
+    let config = ProcessorConfig::default();
+    let mut processor = match &options.load_state_path {
+        Some(path) => {
+            let (client_info, tx_map) = load_state(Path::new(path))?;
+            TransactionProcessor::with_state(&config, client_info, tx_map)
+        }
+        None => TransactionProcessor::new(&config),
+    };
+    #[cfg(feature = "watch")]
+    if options.watch {
+        return watch(options, &mut processor);
+    }
+    process_transactions_from_path_into(&options.file_path, &mut processor)?;
+
+    // every client is still processed since transfers move funds between clients
+    let report = processor.into_report();
+    let selected = Some(&options.clients).filter(|clients| !clients.is_empty());
+    match (options.verbose, selected) {
+        _ if options.json => write_client_info_json_to_writer(
+            &report.client_info,
+            &report.stats,
+            selected,
+            io::stdout(),
+        )?,
+        (true, _) => write_client_info_with_stats_to_writer(
+            &report.client_info,
+            &report.client_stats,
+            selected,
+            io::stdout(),
+        )?,
+        (false, Some(clients)) => {
+            write_selected_client_info_to_writer(&report.client_info, clients, io::stdout())?
         }
+        (false, None) => write_client_info(&report.client_info)?,
+    }
+    warn_skipped(&report.skipped);
+
+    if let Some(path) = &options.save_state_path {
+        save_state(&report.client_info, &report.tx_map, Path::new(path))?;
+    }
+    if let Some(audit_path) = &options.audit_path {
+        write_transaction_log_to_writer(&report.tx_map, File::create(audit_path)?)?;
     }
     Ok(())
 }
 
-fn process_transactions_from_path(path: &str) -> Result<(), Error> {
-    // create a reader for the csv file
-    let mut rdr = csv::ReaderBuilder::new().
-        trim(Trim::All).
-        flexible(true). // to allow amount to be skipped in case of disputes, resolutions and chargebacks
-        from_path(path)?;
-
-    // Reading into a ByteRecord instead of a StringRecord for best performance
-    let raw_record = csv::ByteRecord::new();
-
-    let mut client_info: HashMap<u16, ClientInfo> = HashMap::new();
+// one json line per skipped record, so the error stream can be parsed apart from the csv
+fn warn_skipped(skipped: &[RecordError]) {
+    for record_error in skipped {
+        eprintln!(
+            "{}",
+            json!({
+                "level": "warn",
+                "row": record_error.row,
+                "client": record_error.client,
+                "tx": record_error.tx,
+                "message": record_error.reason.to_string(),
+            })
+        );
+    }
+}
 
-    process_transactions(&mut rdr, raw_record, &mut client_info)?;
-    write_client_info(&client_info)?;
-    Ok(())
+// write every client once the whole file is processed, then only the rows of the clients
+// changed by each batch of appended records
+#[cfg(feature = "watch")]
+fn watch(options: &Options, processor: &mut TransactionProcessor) -> Result<(), Error> {
+    let mut first_update = true;
+    let mut warned = 0;
+    watch_file(
+        Path::new(&options.file_path),
+        processor,
+        |processor, changed| {
+            let include =
+                |client: &u16| options.clients.is_empty() || options.clients.contains(client);
+            let clients: HashSet<u16> = match first_update {
+                true => processor
+                    .client_info()
+                    .keys()
+                    .copied()
+                    .filter(include)
+                    .collect(),
+                false => changed.iter().copied().filter(include).collect(),
+            };
+            first_update = false;
+
+            match options.verbose {
+                true => write_client_info_with_stats_to_writer(
+                    processor.client_info(),
+                    processor.client_stats(),
+                    Some(&clients),
+                    io::stdout(),
+                )?,
+                false => write_selected_client_info_to_writer(
+                    processor.client_info(),
+                    &clients,
+                    io::stdout(),
+                )?,
+            }
+            warn_skipped(&processor.skipped()[warned..]);
+            warned = processor.skipped().len();
+            Ok(())
+        },
+    )
 }
 
-fn write_client_info(client_info: &HashMap<u16, ClientInfo>) -> Result<(), Error> {
-    let mut wtr = csv::Writer::from_writer(io::stdout());
-    // write headers
-    wtr.write_record(&["client", "available", "held", "total", "locked"])?;
-    for (client, info) in client_info.iter() {
-        wtr.serialize((
-            client,
-            &info.available,
-            &info.held,
-            &info.total,
-            &info.locked,
-        ))?;
-    }
+// load a saved state, recompute its held balances and save it again, in place unless
+// --save-state is given
+fn rebalance(state_path: &str, options: &Options) -> Result<(), Error> {
+    let (mut client_info, tx_map) = load_state(Path::new(state_path))?;
+    rebalance_held_from_tx_map(&mut client_info, &tx_map);
 
-    // flush the writer
-    wtr.flush()?;
-    Ok(())
+    let save_path = options.save_state_path.as_deref().unwrap_or(state_path);
+    save_state(&client_info, &tx_map, Path::new(save_path))
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    // assert that there is only one argument provided
-    assert_eq!(args.len(), 2);
-    let file_path = &args[1];
-    match process_transactions_from_path(file_path) {
-        Ok(_) => {}
-        Err(e) => {
-            println!("Error processing transactions: {:?}", e);
+    #[cfg(feature = "logging")]
+    env_logger::init();
+
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n{}", message, usage());
+            process::exit(2);
         }
     };
+
+    // stdout only carries the csv output, errors go to stderr
+    let result = match &options.rebalance_path {
+        Some(state_path) => rebalance(state_path, &options),
+        None => run(&options),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", json!({ "level": "error", "message": e.to_string() }));
+        process::exit(1);
+    }
 }
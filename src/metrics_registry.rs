@@ -0,0 +1,61 @@
+use crate::{
+    process_file_into, validate_processor_config, ClientReport, Error, ProcessorConfig,
+    TransactionProcessor, TransactionType,
+};
+use metrics::{Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit};
+use metrics_util::registry::{AtomicStorage, Registry};
+use std::path::PathBuf;
+
+// the metrics of a run, recorded with atomics so they can be read while and after processing
+pub type MetricsRegistry = Registry<Key, AtomicStorage>;
+
+// records into a registry owned by the caller instead of the global recorder
+struct RegistryRecorder<'a>(&'a MetricsRegistry);
+
+impl Recorder for RegistryRecorder<'_> {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        self.0
+            .get_or_create_counter(key, |counter| Counter::from_arc(counter.clone()))
+    }
+
+    fn register_gauge(&self, key: &Key, _: &Metadata<'_>) -> Gauge {
+        self.0
+            .get_or_create_gauge(key, |gauge| Gauge::from_arc(gauge.clone()))
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        self.0
+            .get_or_create_histogram(key, |histogram| Histogram::from_arc(histogram.clone()))
+    }
+}
+
+// process a file recording its metrics into the given registry only, so that several runs in
+// the same process, like tests or tenants, do not share the global recorder. the registry holds
+// a transactions_processed counter labelled with the type of every record that was read
+pub fn process_transactions_with_metrics_registry(
+    path: PathBuf,
+    registry: &MetricsRegistry,
+    config: ProcessorConfig,
+) -> Result<ClientReport, Error> {
+    validate_processor_config(&config).map_err(Error::InvalidConfig)?;
+
+    let recorder = RegistryRecorder(registry);
+    let mut processor = TransactionProcessor::new(&config);
+    metrics::with_local_recorder(&recorder, || process_file_into(&path, &mut processor))?;
+    Ok(processor.into_report())
+}
+
+// counted before any rule is checked, so skipped and ignored records are included
+pub(crate) fn record_processed(tx_type: &[u8]) {
+    if let Some(tx_type) = TransactionType::from_bytes(tx_type) {
+        // the names of the known types are ascii
+        let name = std::str::from_utf8(tx_type.as_bytes()).unwrap_or_default();
+        metrics::counter!("transactions_processed", "type" => name).increment(1);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::{
+    validate_processor_config, ClientInfo, Error, ProcessorConfig, TransactionEntryOwned,
+    TransactionProcessor, TransactionType,
+};
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+// process every shard on the rayon thread pool and merge the resulting clients. each shard must
+// hold all of the records of its clients in their original order, which shard_by_client
+// guarantees. transfers and the chargeback fee touch two clients and are therefore rejected
+pub fn process_transactions_parallel(
+    shards: Vec<Vec<TransactionEntryOwned>>,
+    config: &ProcessorConfig,
+) -> Result<HashMap<u16, ClientInfo>, Error> {
+    validate_processor_config(config).map_err(Error::InvalidConfig)?;
+
+    if config.fee_on_chargeback.is_some() {
+        return Err(Error::UnexpectedError(
+            "The chargeback fee cannot be charged in parallel shards".into(),
+        ));
+    }
+
+    if let Some(entry) = shards
+        .iter()
+        .flatten()
+        .find(|entry| entry.tx_type == TransactionType::Transfer)
+    {
+        return Err(Error::UnexpectedError(format!(
+            "Transfer {} cannot be processed in parallel shards",
+            entry.tx
+        )));
+    }
+
+    let shard_results: Vec<HashMap<u16, ClientInfo>> = shards
+        .par_iter()
+        .map(|shard| {
+            let mut processor = TransactionProcessor::new(config);
+            for entry in shard {
+                processor.apply_entry(entry)?;
+            }
+            Ok(processor.into_report().client_info)
+        })
+        .collect::<Result<_, Error>>()?;
+
+    let mut client_info = HashMap::new();
+    for shard_clients in shard_results {
+        for (client, info) in shard_clients {
+            if client_info.insert(client, info).is_some() {
+                return Err(Error::UnexpectedError(format!(
+                    "Client id {} appears in more than one shard",
+                    client
+                )));
+            }
+        }
+    }
+    Ok(client_info)
+}
+
+// pre-sort step for unsorted input, grouping the records by client while keeping the order of
+// each client's records
+pub fn shard_by_client(entries: Vec<TransactionEntryOwned>) -> Vec<Vec<TransactionEntryOwned>> {
+    let mut shards: HashMap<u16, Vec<TransactionEntryOwned>> = HashMap::new();
+    for entry in entries {
+        shards.entry(entry.client).or_default().push(entry);
+    }
+    shards.into_values().collect()
+}
@@ -0,0 +1,1629 @@
+#[cfg(feature = "checksum")]
+use crate::checksum::verify_checksum;
+#[cfg(feature = "metrics")]
+use crate::metrics_registry::record_processed;
+use crate::{
+    is_blank_record, validate_processor_config, AccountClosureResult, AccountEvent, Amount,
+    BalanceField, BelowZeroPolicy, ClientInfo, ClientMap, ClientReport, ClientStats, ClosureReason,
+    DisputeOutcomeSummary, DisputeStage, DuplicateTransactionPolicy, Error, ErrorPolicy,
+    HeldBalanceDiscrepancy, MapHasher, NullWalletValidator, ProcessingEvent, ProcessorConfig,
+    ProcessorStats, RecordError, SkipReason, Transaction, TransactionEntry, TransactionEntryOwned,
+    TransactionType, TxMap, WalletValidator, MAX_DECIMAL_PLACES,
+};
+use ahash::RandomState;
+use csv::{ByteRecord, Reader};
+use lru::LruCache;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::io::{self, Write};
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Instant;
+
+type Observer<'a> = Box<dyn FnMut(&ProcessingEvent) + 'a>;
+
+// holds the state of every client and transaction while the records are applied one by one
+pub struct TransactionProcessor<'a> {
+    config: &'a ProcessorConfig,
+    client_info: ClientMap,
+    tx_map: TxMap,
+    skipped: Vec<RecordError>,
+    // number of records read of each type
+    transaction_counts: HashMap<TransactionType, u64>,
+    // number of records applied of each type
+    stats: ProcessorStats,
+    // called with every change applied to the funds of a client
+    observer: Option<Observer<'a>>,
+    // records that could not be parsed or applied under ErrorPolicy::CollectErrors
+    errors: Vec<(u64, Error)>,
+    // number of the record currently being applied, starting at 1
+    row: u64,
+    // row of the last accepted withdrawal or transfer out of each client, for the withdrawal
+    // cooldown
+    last_withdrawal_row: HashMap<u16, u64>,
+    // with tx_cache_size, the ids in tx_map ordered by their last use, the least recently used
+    // transaction is evicted from tx_map once the cache is full
+    tx_recency: Option<LruCache<u32, ()>>,
+    // activity of each client, also used by check_held_balance_consistency
+    client_stats: HashMap<u16, ClientStats>,
+    // when processing started and the size of the input in bytes if known, for the progress line
+    started: Instant,
+    input_len: Option<u64>,
+    // with record_event_log, every operation applied to the accounts
+    event_log: Option<Vec<AccountEvent>>,
+    // with skip_duplicate_records, the hashes of the raw records read so far. a best-effort filter,
+    // two different records with the same hash are seen as duplicates
+    seen_records: HashSet<u64>,
+    record_hasher: RandomState,
+    // asked before a deposit or transfer creates a client
+    wallet_validator: Arc<dyn WalletValidator + Send + Sync>,
+    // with audit_trail_path, the file opened on the first event, and the error of the last write
+    // that failed, returned once the record is applied
+    audit_trail: Option<File>,
+    audit_error: Option<io::Error>,
+    // set for the throwaway processors of dry runs and simulations, which must not touch anything
+    // outside of the processor
+    simulated: bool,
+}
+
+// a line of the audit trail
+#[derive(Serialize)]
+struct AuditLine<'e> {
+    row: u64,
+    #[serde(flatten)]
+    event: &'e ProcessingEvent,
+}
+
+impl<'a> TransactionProcessor<'a> {
+    pub fn new(config: &'a ProcessorConfig) -> Self {
+        TransactionProcessor {
+            config,
+            client_info: ClientMap::with_hasher(MapHasher::new(config.hash_algorithm)),
+            tx_map: TxMap::with_hasher(MapHasher::new(config.hash_algorithm)),
+            skipped: Vec::new(),
+            transaction_counts: HashMap::new(),
+            stats: ProcessorStats::default(),
+            observer: None,
+            errors: Vec::new(),
+            row: 0,
+            last_withdrawal_row: HashMap::new(),
+            tx_recency: config
+                .tx_cache_size
+                .and_then(NonZeroUsize::new)
+                .map(LruCache::new),
+            client_stats: HashMap::new(),
+            started: Instant::now(),
+            input_len: None,
+            event_log: config.record_event_log.then(Vec::new),
+            seen_records: HashSet::new(),
+            record_hasher: RandomState::new(),
+            wallet_validator: Arc::new(NullWalletValidator),
+            audit_trail: None,
+            audit_error: None,
+            simulated: false,
+        }
+    }
+
+    // resume from a state saved by an earlier run. the deposits and withdrawals of the earlier
+    // run are not known, so check_held_balance_consistency only covers the new records
+    pub fn with_state(
+        config: &'a ProcessorConfig,
+        client_info: HashMap<u16, ClientInfo>,
+        tx_map: HashMap<u32, Transaction>,
+    ) -> Self {
+        let mut processor = TransactionProcessor::new(config);
+        processor.client_info.extend(client_info);
+        if processor.tx_recency.is_none() {
+            processor.tx_map.extend(tx_map);
+            return processor;
+        }
+
+        // fill the cache in id order so that the most recent transactions are kept
+        let mut transactions: Vec<(u32, Transaction)> = tx_map.into_iter().collect();
+        transactions.sort_unstable_by_key(|(tx_id, _)| *tx_id);
+        for (tx_id, tx) in transactions {
+            processor.insert_transaction(tx_id, tx);
+        }
+        processor
+    }
+
+    pub fn set_observer<F: FnMut(&ProcessingEvent) + 'a>(&mut self, observer: F) {
+        self.observer = Some(Box::new(observer));
+    }
+
+    // continue the row numbers of an earlier run
+    pub(crate) fn set_row(&mut self, row: u64) {
+        self.row = row;
+    }
+
+    // turn off the side effects of processing, writing the audit trail and calling the hooks.
+    // every new client is accepted without asking on_new_client
+    pub(crate) fn set_simulated(&mut self) {
+        self.simulated = true;
+    }
+
+    pub fn set_wallet_validator(
+        &mut self,
+        wallet_validator: Arc<dyn WalletValidator + Send + Sync>,
+    ) {
+        self.wallet_validator = wallet_validator;
+    }
+
+    // the size of the input in bytes, used to estimate the completion of the progress line
+    pub fn set_input_len(&mut self, bytes: u64) {
+        self.input_len = Some(bytes);
+    }
+
+    pub fn config(&self) -> &ProcessorConfig {
+        self.config
+    }
+
+    pub fn client_info(&self) -> &ClientMap {
+        &self.client_info
+    }
+
+    pub fn tx_map(&self) -> &TxMap {
+        &self.tx_map
+    }
+
+    pub fn skipped(&self) -> &[RecordError] {
+        &self.skipped
+    }
+
+    pub fn transaction_counts(&self) -> &HashMap<TransactionType, u64> {
+        &self.transaction_counts
+    }
+
+    pub fn stats(&self) -> &ProcessorStats {
+        &self.stats
+    }
+
+    pub fn client_stats(&self) -> &HashMap<u16, ClientStats> {
+        &self.client_stats
+    }
+
+    // the number of records read so far
+    pub fn rows(&self) -> u64 {
+        self.row
+    }
+
+    pub fn errors(&self) -> &[(u64, Error)] {
+        &self.errors
+    }
+
+    // clients whose available or held balance differs from what their deposits, withdrawals and
+    // open disputes add up to. walks tx_map once per client so it is meant for diagnostics, and
+    // open disputes evicted by tx_cache_size show up as discrepancies
+    pub fn check_held_balance_consistency(&self) -> Vec<HeldBalanceDiscrepancy> {
+        let mut discrepancies: Vec<HeldBalanceDiscrepancy> = self
+            .client_info
+            .iter()
+            .filter_map(|(client_id, funds)| {
+                let (deposited, withdrawn) = match self.client_stats.get(client_id) {
+                    Some(stats) => (stats.total_deposited, stats.total_withdrawn),
+                    None => (Amount::ZERO, Amount::ZERO),
+                };
+                let expected_available =
+                    compute_expected_available(*client_id, &self.tx_map, deposited, withdrawn);
+                let expected_held = open_dispute_amount(*client_id, &self.tx_map);
+                if funds.available == expected_available && funds.held == expected_held {
+                    return None;
+                }
+                Some(HeldBalanceDiscrepancy {
+                    client: *client_id,
+                    expected_available,
+                    available: funds.available,
+                    expected_held,
+                    held: funds.held,
+                })
+            })
+            .collect();
+        discrepancies.sort_by_key(|discrepancy| discrepancy.client);
+        discrepancies
+    }
+
+    pub fn into_report(self) -> ClientReport {
+        ClientReport {
+            client_info: self.client_info.into_iter().collect(),
+            tx_map: self.tx_map.into_iter().collect(),
+            skipped: self.skipped,
+            stats: self.stats,
+            client_stats: self.client_stats,
+            event_log: self.event_log,
+        }
+    }
+
+    // the report along with every collected error, including the skipped records, ordered by row
+    pub fn into_report_with_errors(mut self) -> (ClientReport, Vec<(u64, Error)>) {
+        let mut errors = std::mem::take(&mut self.errors);
+        errors.extend(
+            self.skipped
+                .iter()
+                .map(|record_error| (record_error.row, record_error.clone().into())),
+        );
+        errors.sort_by_key(|(row, _)| *row);
+        (self.into_report(), errors)
+    }
+
+    // parse and apply the next record, under ErrorPolicy::CollectErrors a record that fails is
+    // collected instead of returned
+    pub(crate) fn apply_byte_record(
+        &mut self,
+        raw_record: &ByteRecord,
+        headers: &ByteRecord,
+    ) -> Result<(), Error> {
+        if is_blank_record(raw_record) {
+            return Ok(());
+        }
+
+        self.row += 1;
+        #[cfg(feature = "checksum")]
+        if self.config.verify_checksums {
+            if let Err(e) = verify_checksum(raw_record, headers, self.row) {
+                return self.collect_error(Err(e));
+            }
+        }
+        let is_duplicate = self.config.skip_duplicate_records
+            && !self.seen_records.insert(self.hash_record(raw_record));
+        let result = raw_record
+            .deserialize(Some(headers))
+            .map_err(Error::from)
+            .and_then(|record: TransactionEntry| match is_duplicate {
+                // a retried row is expected, so it does not stop strict processing
+                true => {
+                    self.record_skipped(RecordError {
+                        row: self.row,
+                        client: record.client,
+                        tx: record.tx,
+                        reason: SkipReason::DuplicateRecord,
+                    });
+                    Ok(())
+                }
+                false => self.apply_single(&record),
+            });
+        self.collect_error(result)
+    }
+
+    fn hash_record(&self, raw_record: &ByteRecord) -> u64 {
+        let mut hasher = self.record_hasher.build_hasher();
+        for field in raw_record {
+            field.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    // apply a record that was not read from csv, following the same error policy as
+    // apply_byte_record
+    pub fn apply_entry(&mut self, entry: &TransactionEntryOwned) -> Result<(), Error> {
+        self.row += 1;
+        let result = self.apply_single(&entry.into());
+        self.collect_error(result)
+    }
+
+    // a record that could not be read still counts as a row, following the same error policy as
+    // apply_byte_record
+    pub fn fail_row(&mut self, e: Error) -> Result<(), Error> {
+        self.row += 1;
+        self.collect_error(Err(e))
+    }
+
+    // with print_progress_every_n, print the number of rows processed so far to stderr
+    fn print_progress(&self, bytes_read: u64) {
+        match self.config.print_progress_every_n {
+            Some(every_n) if self.row.is_multiple_of(every_n) => {}
+            _ => return,
+        }
+
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let rows_per_second = if elapsed > 0.0 {
+            self.row as f64 / elapsed
+        } else {
+            0.0
+        };
+        match self.input_len {
+            Some(input_len) if input_len > 0 => eprintln!(
+                "Processed {} rows... ({:.0} rows/s, {:.1}% done)",
+                self.row,
+                rows_per_second,
+                (bytes_read as f64 / input_len as f64 * 100.0).min(100.0)
+            ),
+            _ => eprintln!(
+                "Processed {} rows... ({:.0} rows/s)",
+                self.row, rows_per_second
+            ),
+        }
+    }
+
+    fn collect_error(&mut self, result: Result<(), Error>) -> Result<(), Error> {
+        match result {
+            Err(e) if self.config.error_policy == ErrorPolicy::CollectErrors => {
+                self.errors.push((self.row, e));
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    fn apply_single(&mut self, record: &TransactionEntry) -> Result<(), Error> {
+        if let Some(tx_type) = TransactionType::from_bytes(record.tx_type) {
+            *self.transaction_counts.entry(tx_type).or_insert(0) += 1;
+        }
+
+        // only the debugged client pays for the extra clone and logging
+        #[cfg(feature = "logging")]
+        let before = match self.config.debug_client {
+            Some(client) if client == record.client => Some(self.client_info.get(&client).cloned()),
+            _ => None,
+        };
+
+        let available_before = self
+            .client_info
+            .get(&record.client)
+            .map(|info| info.available);
+        let mut result = self
+            .apply_record(record)
+            .and_then(|()| self.check_below_zero(record.client, available_before));
+        if let Some(e) = self.audit_error.take() {
+            result = result.and(Err(e.into()));
+        }
+
+        #[cfg(feature = "logging")]
+        if let Some(before) = before {
+            log::debug!(
+                "client {} {} tx {} amount {:?}: before {:?} after {:?} result {:?}",
+                record.client,
+                String::from_utf8_lossy(record.tx_type),
+                record.tx,
+                record.amount,
+                before,
+                self.client_info.get(&record.client),
+                result
+            );
+        }
+
+        result
+    }
+
+    fn apply_record(&mut self, record: &TransactionEntry) -> Result<(), Error> {
+        #[cfg(feature = "metrics")]
+        record_processed(record.tx_type);
+
+        if self.config.require_amount_for_all_types && record.amount.is_none() {
+            return self.skip(record, SkipReason::MissingAmount);
+        }
+
+        // if the client is locked, continue. a single lookup since this runs for every record
+        if let Some(client) = self.client_info.get(&record.client) {
+            if client.locked {
+                return Ok(());
+            }
+        }
+
+        match record.tx_type {
+            b"deposit" => {
+                if self.tx_map.contains_key(&record.tx) {
+                    return Ok(());
+                }
+
+                // if record.amount is None, partner side error, ignore and continue to next transaction
+                let amount = match record.amount {
+                    Some(amt) => amt,
+                    None => return Ok(()),
+                };
+
+                // a negative deposit would withdraw funds without any check
+                if amount <= Amount::ZERO {
+                    return self.skip(record, SkipReason::NonPositiveAmount { amount });
+                }
+
+                if exceeds_precision(amount, MAX_DECIMAL_PLACES) {
+                    return self.skip(
+                        record,
+                        SkipReason::PrecisionExceeded {
+                            amount,
+                            max_dp: MAX_DECIMAL_PLACES,
+                        },
+                    );
+                }
+
+                // a client is only created by a deposit of at least the minimum
+                if let Some(minimum) = self.config.client_creation_requires_minimum_deposit {
+                    if amount < minimum && !self.client_info.contains_key(&record.client) {
+                        let reason = SkipReason::BelowMinimumInitialDeposit { amount, minimum };
+                        return self.skip(record, reason);
+                    }
+                }
+
+                if !self.client_info.contains_key(&record.client) {
+                    if !self.wallet_validator.is_valid_client(record.client) {
+                        let reason = SkipReason::InvalidWallet {
+                            client: record.client,
+                        };
+                        return self.skip(record, reason);
+                    }
+                    if !self.accept_new_client(record.client) {
+                        return self.skip(record, SkipReason::NewClientRejected);
+                    }
+                }
+
+                // with a balance cap only the part of the deposit that fits under the cap is accepted
+                let total = self
+                    .client_info
+                    .get(&record.client)
+                    .map_or(Amount::ZERO, |funds| funds.total);
+                let accepted = match self.config.client_balance_cap {
+                    Some(cap) if total + amount > cap => (cap - total).max(Amount::ZERO),
+                    _ => amount,
+                };
+                // a fully rejected deposit leaves no trace of the client
+                if accepted.is_zero() {
+                    let reason = SkipReason::BalanceCapExceeded {
+                        accepted,
+                        rejected: amount,
+                    };
+                    return self.skip(record, reason);
+                }
+
+                let client_funds = self
+                    .client_info
+                    .entry(record.client)
+                    .or_insert_with(ClientInfo::empty);
+                client_funds.available += accepted;
+                client_funds.total += accepted;
+                let client_stats = self.client_stats.entry(record.client).or_default();
+                client_stats.total_deposited += accepted;
+                client_stats.deposit_count += 1;
+                self.stats.deposits += 1;
+                self.emit(ProcessingEvent::Deposit {
+                    client: record.client,
+                    tx: record.tx,
+                    amount: accepted,
+                });
+                self.insert_transaction(
+                    record.tx,
+                    Transaction {
+                        tx_id: record.tx,
+                        tx_type: TransactionType::Deposit,
+                        client: record.client,
+                        amount: accepted,
+                        disputed_amount: dec!(0.0),
+                        dispute_stage: DisputeStage::None,
+                        counterparty: None,
+                    },
+                );
+
+                if accepted < amount {
+                    let reason = SkipReason::BalanceCapExceeded {
+                        accepted,
+                        rejected: amount - accepted,
+                    };
+                    return self.skip(record, reason);
+                }
+            }
+            b"withdrawal" => {
+                // a withdrawal reusing a transaction id must not overwrite the original transaction
+                if self.tx_map.contains_key(&record.tx) {
+                    return Ok(());
+                }
+
+                // if amount is none or if the client id is something that have not been seen before, continue to next transaction
+                let amount = match record.amount {
+                    Some(amt) if self.client_info.contains_key(&record.client) => amt,
+                    _ => return Ok(()),
+                };
+
+                if exceeds_precision(amount, MAX_DECIMAL_PLACES) {
+                    return self.skip(
+                        record,
+                        SkipReason::PrecisionExceeded {
+                            amount,
+                            max_dp: MAX_DECIMAL_PLACES,
+                        },
+                    );
+                }
+
+                if let Some(reason) = self.withdrawal_cooldown(record.client) {
+                    return self.skip(record, reason);
+                }
+
+                let client_funds = match self.client_info.get_mut(&record.client) {
+                    Some(funds) => funds,
+                    None => {
+                        return Err(Error::UnexpectedError(format!(
+                            "Client id {} not found",
+                            record.client
+                        )))
+                    }
+                };
+
+                // only withdraw if there are enough available funds
+                if client_funds.available < amount {
+                    let reason = SkipReason::InsufficientFunds {
+                        available: client_funds.available,
+                        requested: amount,
+                    };
+                    return self.skip(record, reason);
+                }
+
+                client_funds.available -= amount;
+                client_funds.total -= amount;
+                let client_stats = self.client_stats.entry(record.client).or_default();
+                client_stats.total_withdrawn += amount;
+                client_stats.withdrawal_count += 1;
+                self.last_withdrawal_row.insert(record.client, self.row);
+                self.stats.withdrawals += 1;
+                self.emit(ProcessingEvent::Withdrawal {
+                    client: record.client,
+                    tx: record.tx,
+                    amount,
+                });
+
+                self.insert_transaction(
+                    record.tx,
+                    Transaction {
+                        tx_id: record.tx,
+                        tx_type: TransactionType::Withdrawal,
+                        client: record.client,
+                        amount,
+                        disputed_amount: dec!(0.0),
+                        dispute_stage: DisputeStage::None,
+                        counterparty: None,
+                    },
+                );
+            }
+            b"transfer" => {
+                if self.tx_map.contains_key(&record.tx) {
+                    return Ok(());
+                }
+
+                // a transfer needs an amount, a target other than the sender and a sender that has been seen before
+                let (amount, target) = match (record.amount, record.target) {
+                    (Some(amt), Some(target))
+                        if target != record.client
+                            && self.client_info.contains_key(&record.client) =>
+                    {
+                        (amt, target)
+                    }
+                    _ => return Ok(()),
+                };
+
+                // a negative transfer would move funds from the target to the sender
+                if amount <= Amount::ZERO {
+                    return self.skip(record, SkipReason::NonPositiveAmount { amount });
+                }
+
+                if exceeds_precision(amount, MAX_DECIMAL_PLACES) {
+                    return self.skip(
+                        record,
+                        SkipReason::PrecisionExceeded {
+                            amount,
+                            max_dp: MAX_DECIMAL_PLACES,
+                        },
+                    );
+                }
+
+                // a transfer out is a withdrawal for the cooldown
+                if let Some(reason) = self.withdrawal_cooldown(record.client) {
+                    return self.skip(record, reason);
+                }
+
+                // a locked target cannot receive funds, ignore and continue to next transaction
+                if self
+                    .client_info
+                    .get(&target)
+                    .is_some_and(|funds| funds.locked)
+                {
+                    return Ok(());
+                }
+
+                let sender_funds = match self.client_info.get(&record.client) {
+                    Some(funds) => funds,
+                    None => {
+                        return Err(Error::UnexpectedError(format!(
+                            "Client id {} not found",
+                            record.client
+                        )))
+                    }
+                };
+
+                // skip both sides of the transfer if the sender cannot cover it
+                if sender_funds.available < amount {
+                    let reason = SkipReason::InsufficientFunds {
+                        available: sender_funds.available,
+                        requested: amount,
+                    };
+                    return self.skip(record, reason);
+                }
+
+                // a transfer counts as a deposit to its target, but one crossing the balance cap
+                // is skipped as a whole instead of being split
+                if let Some(cap) = self.config.client_balance_cap {
+                    let target_total = self
+                        .client_info
+                        .get(&target)
+                        .map_or(Amount::ZERO, |funds| funds.total);
+                    if target_total + amount > cap {
+                        let reason = SkipReason::BalanceCapExceeded {
+                            accepted: Amount::ZERO,
+                            rejected: amount,
+                        };
+                        return self.skip(record, reason);
+                    }
+                }
+
+                // the target is created like by a deposit, asking on_new_client last since it
+                // reaches external systems
+                if !self.client_info.contains_key(&target) {
+                    if !self.wallet_validator.is_valid_client(target) {
+                        return self.skip(record, SkipReason::InvalidWallet { client: target });
+                    }
+                    // the transfer is the first deposit of the target
+                    if let Some(minimum) = self.config.client_creation_requires_minimum_deposit {
+                        if amount < minimum {
+                            let reason = SkipReason::BelowMinimumInitialDeposit { amount, minimum };
+                            return self.skip(record, reason);
+                        }
+                    }
+                    if !self.accept_new_client(target) {
+                        return self.skip(record, SkipReason::NewClientRejected);
+                    }
+                }
+
+                if let Some(sender_funds) = self.client_info.get_mut(&record.client) {
+                    sender_funds.available -= amount;
+                    sender_funds.total -= amount;
+                }
+
+                let target_funds = self
+                    .client_info
+                    .entry(target)
+                    .or_insert_with(ClientInfo::empty);
+                target_funds.available += amount;
+                target_funds.total += amount;
+
+                self.client_stats
+                    .entry(record.client)
+                    .or_default()
+                    .total_withdrawn += amount;
+                self.client_stats.entry(target).or_default().total_deposited += amount;
+                self.last_withdrawal_row.insert(record.client, self.row);
+                self.stats.transfers += 1;
+                self.emit(ProcessingEvent::Transfer {
+                    client: record.client,
+                    tx: record.tx,
+                    amount,
+                    target,
+                });
+
+                self.insert_transaction(
+                    record.tx,
+                    Transaction {
+                        tx_id: record.tx,
+                        tx_type: TransactionType::Transfer,
+                        client: record.client,
+                        amount,
+                        disputed_amount: dec!(0.0),
+                        dispute_stage: DisputeStage::None,
+                        counterparty: Some(target),
+                    },
+                );
+            }
+            b"dispute" => {
+                if !self.tx_map.contains_key(&record.tx)
+                    || !self.client_info.contains_key(&record.client)
+                {
+                    return Ok(());
+                }
+                self.touch_transaction(record.tx);
+
+                let tx = match self.tx_map.get_mut(&record.tx) {
+                    Some(tx) => tx,
+                    None => {
+                        return Err(Error::UnexpectedError(format!(
+                            "Transaction id {} not found",
+                            record.tx
+                        )))
+                    }
+                };
+
+                // a client can only act on its own transactions
+                if tx.client != record.client {
+                    let reason = SkipReason::ClientMismatch {
+                        tx_client: tx.client,
+                    };
+                    return self.skip(record, reason);
+                }
+
+                // if dispute stage is not None, continue
+                if tx.dispute_stage != DisputeStage::None {
+                    return Ok(());
+                }
+
+                // a dispute may name the part of the transaction being disputed, otherwise the full amount is held
+                let partial_amount = record.amount.unwrap_or(tx.amount);
+
+                let tx_type = tx.tx_type;
+                let tx_amount = tx.amount;
+                // a transfer only keeps the sender side, so charging it back would debit the
+                // sender a second time while the target keeps the funds
+                if tx_type == TransactionType::Transfer
+                    || self.config.disable_dispute_for_types.contains(&tx_type)
+                {
+                    return self.skip(record, SkipReason::DisputeNotAllowedForType { tx_type });
+                }
+                if partial_amount <= Amount::ZERO || partial_amount > tx_amount {
+                    let reason = SkipReason::InvalidDisputeAmount {
+                        amount: partial_amount,
+                        tx_amount,
+                    };
+                    return self.skip(record, reason);
+                }
+
+                // large disputes wait for a manual review instead of holding the funds
+                match self.config.max_dispute_amount {
+                    Some(max) if partial_amount > max => {
+                        if let Some(tx) = self.tx_map.get_mut(&record.tx) {
+                            tx.disputed_amount = partial_amount;
+                            tx.dispute_stage = DisputeStage::PendingReview;
+                        }
+                        self.pin_transaction(record.tx);
+                        log::info!(
+                            "dispute of {} on tx {} of client {} is pending review",
+                            partial_amount,
+                            record.tx,
+                            record.client
+                        );
+                        self.stats.disputes_pending_review += 1;
+                        self.emit(ProcessingEvent::DisputePendingReview {
+                            client: record.client,
+                            tx: record.tx,
+                            amount: partial_amount,
+                        });
+                        return Ok(());
+                    }
+                    _ => {}
+                }
+                let event = match apply_dispute_with_partial_amount(
+                    record.tx,
+                    partial_amount,
+                    &mut self.client_info,
+                    &mut self.tx_map,
+                ) {
+                    Ok(event) => event,
+                    Err(_) => return Ok(()), // partner side error, ignore and continue to next transaction
+                };
+                self.pin_transaction(record.tx);
+                self.stats.disputes += 1;
+                self.emit(event);
+            }
+            b"resolve" => {
+                if !self.tx_map.contains_key(&record.tx)
+                    || !self.client_info.contains_key(&record.client)
+                {
+                    return Ok(());
+                }
+                self.touch_transaction(record.tx);
+
+                let tx = match self.tx_map.get_mut(&record.tx) {
+                    Some(tx) => tx,
+                    None => {
+                        return Err(Error::UnexpectedError(format!(
+                            "Transaction id {} not found",
+                            record.tx
+                        )))
+                    }
+                };
+
+                // a client can only act on its own transactions
+                if tx.client != record.client {
+                    let reason = SkipReason::ClientMismatch {
+                        tx_client: tx.client,
+                    };
+                    return self.skip(record, reason);
+                }
+
+                let client_funds = match self.client_info.get_mut(&record.client) {
+                    Some(funds) => funds,
+                    None => return Ok(()), // partner side error, ignore and continue to next transaction
+                };
+
+                // a pending review held nothing, so dismissing it releases nothing
+                let amount = match tx.dispute_stage {
+                    DisputeStage::Open => tx.disputed_amount,
+                    DisputeStage::PendingReview => Amount::ZERO,
+                    _ => return Ok(()),
+                };
+                client_funds.available += amount;
+                client_funds.held -= amount;
+                // a resolved dispute is final, so it cannot be resolved or charged back again
+                tx.dispute_stage = DisputeStage::Resolved;
+                self.unpin_transaction(record.tx);
+                self.stats.resolves += 1;
+                self.emit(ProcessingEvent::Resolved {
+                    client: record.client,
+                    tx: record.tx,
+                    amount,
+                });
+            }
+            b"chargeback" => {
+                if !self.tx_map.contains_key(&record.tx)
+                    || !self.client_info.contains_key(&record.client)
+                {
+                    return Ok(());
+                }
+                self.touch_transaction(record.tx);
+
+                let tx = match self.tx_map.get_mut(&record.tx) {
+                    Some(tx) => tx,
+                    None => {
+                        return Err(Error::UnexpectedError(format!(
+                            "Transaction id {} not found",
+                            record.tx
+                        )))
+                    }
+                };
+
+                // a client can only act on its own transactions
+                if tx.client != record.client {
+                    let reason = SkipReason::ClientMismatch {
+                        tx_client: tx.client,
+                    };
+                    return self.skip(record, reason);
+                }
+
+                if !is_active_dispute(tx.dispute_stage) {
+                    return Ok(());
+                }
+
+                let client_funds = match self.client_info.get_mut(&record.client) {
+                    Some(funds) => funds,
+                    None => return Ok(()), // partner side error, ignore and continue to next transaction
+                };
+
+                // a pending review held nothing, so the funds are taken from the available ones
+                if tx.dispute_stage == DisputeStage::PendingReview {
+                    client_funds.total -= tx.disputed_amount;
+                    client_funds.available -= tx.disputed_amount;
+                }
+                // held should always cover the open disputes, if it drifted it is a bug and the
+                // chargeback must not turn the balances negative
+                else if client_funds.held < tx.disputed_amount {
+                    log::error!(
+                        "held funds underflow on chargeback of tx {} for client {}, {} held but {} disputed",
+                        record.tx,
+                        record.client,
+                        client_funds.held,
+                        tx.disputed_amount
+                    );
+                    if self.config.error_policy == ErrorPolicy::Strict {
+                        return Err(Error::UnexpectedError(
+                            "held funds underflow on chargeback".to_string(),
+                        ));
+                    }
+                    log::warn!(
+                        "clamping held of client {} to zero, discrepancy of {}",
+                        record.client,
+                        tx.disputed_amount - client_funds.held
+                    );
+                    client_funds.total -= tx.disputed_amount;
+                    client_funds.held = Amount::ZERO;
+                } else {
+                    client_funds.total -= tx.disputed_amount;
+                    client_funds.held -= tx.disputed_amount;
+                }
+                tx.dispute_stage = DisputeStage::ChargeBack;
+                let amount = tx.disputed_amount;
+                self.client_stats
+                    .entry(record.client)
+                    .or_default()
+                    .total_withdrawn += amount;
+
+                // lock the clients account
+                client_funds.locked = true;
+                self.unpin_transaction(record.tx);
+                self.stats.chargebacks += 1;
+                self.emit(ProcessingEvent::ChargeBack {
+                    client: record.client,
+                    tx: record.tx,
+                    amount,
+                });
+                self.notify_chargeback(record.client, record.tx, amount);
+                self.charge_merchant_fee(record);
+            }
+            b"fee" => {
+                // a fee needs a positive amount and a client that has been seen before
+                let amount = match record.amount {
+                    Some(amt)
+                        if amt > Amount::ZERO && self.client_info.contains_key(&record.client) =>
+                    {
+                        amt
+                    }
+                    _ => return Ok(()),
+                };
+
+                if exceeds_precision(amount, MAX_DECIMAL_PLACES) {
+                    return self.skip(
+                        record,
+                        SkipReason::PrecisionExceeded {
+                            amount,
+                            max_dp: MAX_DECIMAL_PLACES,
+                        },
+                    );
+                }
+
+                let client_funds = match self.client_info.get_mut(&record.client) {
+                    Some(funds) => funds,
+                    None => {
+                        return Err(Error::UnexpectedError(format!(
+                            "Client id {} not found",
+                            record.client
+                        )))
+                    }
+                };
+
+                if client_funds.available < amount {
+                    let reason = SkipReason::InsufficientFunds {
+                        available: client_funds.available,
+                        requested: amount,
+                    };
+                    self.stats.fees_rejected += 1;
+                    return self.skip(record, reason);
+                }
+
+                // fees cannot be disputed, so they are not kept in tx_map
+                client_funds.available -= amount;
+                client_funds.total -= amount;
+                self.client_stats
+                    .entry(record.client)
+                    .or_default()
+                    .total_withdrawn += amount;
+                self.stats.fees_applied += 1;
+                self.emit(ProcessingEvent::Fee {
+                    client: record.client,
+                    tx: record.tx,
+                    amount,
+                });
+            }
+            b"reverse" => {
+                let tx = match self.tx_map.get_mut(&record.tx) {
+                    Some(tx) => tx,
+                    None => return Ok(()), // partner side error, ignore and continue to next transaction
+                };
+
+                // only an undisputed deposit or withdrawal of the same client can be reversed
+                if tx.client != record.client || tx.dispute_stage != DisputeStage::None {
+                    return Ok(());
+                }
+
+                let client_funds = match self.client_info.get_mut(&record.client) {
+                    Some(funds) => funds,
+                    None => return Ok(()), // partner side error, ignore and continue to next transaction
+                };
+
+                match tx.tx_type {
+                    TransactionType::Deposit => {
+                        // the deposited funds may already have been withdrawn
+                        if client_funds.available < tx.amount {
+                            let reason = SkipReason::InsufficientFunds {
+                                available: client_funds.available,
+                                requested: tx.amount,
+                            };
+                            return self.skip(record, reason);
+                        }
+                        client_funds.available -= tx.amount;
+                        client_funds.total -= tx.amount;
+                        self.client_stats
+                            .entry(record.client)
+                            .or_default()
+                            .total_deposited -= tx.amount;
+                    }
+                    TransactionType::Withdrawal => {
+                        client_funds.available += tx.amount;
+                        client_funds.total += tx.amount;
+                        self.client_stats
+                            .entry(record.client)
+                            .or_default()
+                            .total_withdrawn -= tx.amount;
+                    }
+                    _ => return Ok(()),
+                }
+                tx.dispute_stage = DisputeStage::Reversed;
+                let amount = tx.amount;
+                self.stats.reversals += 1;
+                self.emit(ProcessingEvent::Reversed {
+                    client: record.client,
+                    tx: record.tx,
+                    amount,
+                });
+            }
+            _ => {
+                return Ok(()); // partner side error, ignore and continue to next transaction
+            }
+        }
+        Ok(())
+    }
+
+    // the reason to skip a withdrawal or transfer out of a client still in its cooldown
+    fn withdrawal_cooldown(&self, client: u16) -> Option<SkipReason> {
+        let cooldown = self.config.withdrawal_cooldown_rows?;
+        let rows_since = self.row - self.last_withdrawal_row.get(&client)?;
+        (rows_since < cooldown).then_some(SkipReason::WithdrawalCooldown {
+            rows_since,
+            cooldown,
+        })
+    }
+
+    // ask the on_new_client hook whether a client may be created
+    fn accept_new_client(&self, client: u16) -> bool {
+        let hook = match &self.config.on_new_client {
+            Some(hook) if !self.simulated => hook,
+            _ => return true,
+        };
+
+        self.config.reject_new_client.store(false, Ordering::SeqCst);
+        match panic::catch_unwind(AssertUnwindSafe(|| hook(client))) {
+            Ok(()) => !self.config.reject_new_client.load(Ordering::SeqCst),
+            Err(_) => {
+                log::warn!("on_new_client panicked for client {}", client);
+                false
+            }
+        }
+    }
+
+    fn notify_chargeback(&self, client: u16, tx: u32, amount: Amount) {
+        if self.simulated {
+            return;
+        }
+        if let Some(hook) = &self.config.on_chargeback {
+            if panic::catch_unwind(AssertUnwindSafe(|| hook(client, tx, amount))).is_err() {
+                log::warn!("on_chargeback panicked for tx {} of client {}", tx, client);
+            }
+        }
+    }
+
+    // only a record that lowered the available funds below zero is counted, not every later record
+    // of a client that stayed below zero
+    fn check_below_zero(
+        &mut self,
+        client: u16,
+        available_before: Option<Amount>,
+    ) -> Result<(), Error> {
+        let funds = match self.client_info.get_mut(&client) {
+            Some(funds) if funds.available < Amount::ZERO => funds,
+            _ => return Ok(()),
+        };
+        if available_before.is_some_and(|before| funds.available >= before) {
+            return Ok(());
+        }
+
+        self.stats.negative_balance_count += 1;
+        match self.config.on_balance_below_zero {
+            BelowZeroPolicy::Warn => {
+                log::warn!(
+                    "available funds of client {} dropped to {} on row {}",
+                    client,
+                    funds.available,
+                    self.row
+                );
+            }
+            BelowZeroPolicy::Clamp => {
+                log::warn!(
+                    "clamping available funds of client {} from {} to zero on row {}",
+                    client,
+                    funds.available,
+                    self.row
+                );
+                funds.total -= funds.available;
+                funds.available = Amount::ZERO;
+            }
+            BelowZeroPolicy::Abort => {
+                return Err(Error::NegativeBalance {
+                    client,
+                    amount: funds.available,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    // the chargeback is already applied, so a merchant that cannot pay the fee is only reported,
+    // even in strict mode
+    fn charge_merchant_fee(&mut self, record: &TransactionEntry) {
+        let (fee, merchant) = match (
+            self.config.fee_on_chargeback,
+            self.config.merchant_client_id,
+        ) {
+            (Some(fee), Some(merchant)) => (fee, merchant),
+            _ => return,
+        };
+
+        // a locked merchant is left alone like any other locked client
+        match self.client_info.get_mut(&merchant) {
+            Some(merchant_funds) if !merchant_funds.locked && merchant_funds.available >= fee => {
+                merchant_funds.available -= fee;
+                merchant_funds.total -= fee;
+                self.client_stats
+                    .entry(merchant)
+                    .or_default()
+                    .total_withdrawn += fee;
+                self.stats.fees_applied += 1;
+                self.emit(ProcessingEvent::Fee {
+                    client: merchant,
+                    tx: record.tx,
+                    amount: fee,
+                });
+            }
+            merchant_funds => {
+                let reason = SkipReason::MerchantFeeFailed {
+                    merchant,
+                    available: merchant_funds.map_or(Amount::ZERO, |funds| funds.available),
+                    fee,
+                };
+                self.stats.fees_rejected += 1;
+                self.record_skipped(RecordError {
+                    row: self.row,
+                    client: record.client,
+                    tx: record.tx,
+                    reason,
+                });
+            }
+        }
+    }
+
+    fn write_audit_line(&mut self, path: &Path, event: &ProcessingEvent) -> io::Result<()> {
+        let file = match &mut self.audit_trail {
+            Some(file) => file,
+            None => self
+                .audit_trail
+                .insert(OpenOptions::new().create(true).append(true).open(path)?),
+        };
+        let mut line = serde_json::to_vec(&AuditLine {
+            row: self.row,
+            event,
+        })?;
+        line.push(b'\n');
+        // a single write per line, so the lines of concurrent runs do not interleave
+        file.write_all(&line)
+    }
+
+    fn emit(&mut self, event: ProcessingEvent) {
+        if let Some(event_log) = &mut self.event_log {
+            event_log.push(match event {
+                ProcessingEvent::Deposit { client, tx, amount } => {
+                    AccountEvent::Deposit { client, tx, amount }
+                }
+                ProcessingEvent::Withdrawal { client, tx, amount } => {
+                    AccountEvent::Withdrawal { client, tx, amount }
+                }
+                ProcessingEvent::Transfer {
+                    client,
+                    tx,
+                    amount,
+                    target,
+                } => AccountEvent::Transfer {
+                    client,
+                    tx,
+                    amount,
+                    target,
+                },
+                ProcessingEvent::Fee { client, tx, amount } => {
+                    AccountEvent::Fee { client, tx, amount }
+                }
+                ProcessingEvent::Reversed { client, tx, .. } => {
+                    AccountEvent::Reversed { client, tx }
+                }
+                ProcessingEvent::DisputeOpened { client, tx, .. } => {
+                    AccountEvent::DisputeOpened { client, tx }
+                }
+                ProcessingEvent::DisputePendingReview { client, tx, .. } => {
+                    AccountEvent::DisputePendingReview { client, tx }
+                }
+                ProcessingEvent::Resolved { client, tx, .. } => {
+                    AccountEvent::Resolved { client, tx }
+                }
+                ProcessingEvent::ChargeBack { client, tx, .. } => {
+                    AccountEvent::ChargeBack { client, tx }
+                }
+            });
+            // a chargeback always locks the account
+            if let ProcessingEvent::ChargeBack { client, .. } = event {
+                event_log.push(AccountEvent::AccountLocked { client });
+            }
+        }
+        if let Some(path) = self
+            .config
+            .audit_trail_path
+            .as_ref()
+            .filter(|_| !self.simulated)
+        {
+            if let Err(e) = self.write_audit_line(path, &event) {
+                self.audit_error = Some(e);
+            }
+        }
+        if let Some(observer) = &mut self.observer {
+            observer(&event);
+        }
+    }
+
+    fn insert_transaction(&mut self, tx_id: u32, tx: Transaction) {
+        let pinned = is_active_dispute(tx.dispute_stage);
+        self.tx_map.insert(tx_id, tx);
+        if !pinned {
+            self.track_transaction(tx_id);
+        }
+    }
+
+    // a transaction under an active dispute is taken out of the eviction order, so that its held
+    // funds can always be resolved or charged back
+    fn pin_transaction(&mut self, tx_id: u32) {
+        if let Some(recency) = &mut self.tx_recency {
+            recency.pop(&tx_id);
+        }
+    }
+
+    // put a transaction whose dispute ended back in the eviction order, as the most recently used
+    fn unpin_transaction(&mut self, tx_id: u32) {
+        self.track_transaction(tx_id);
+    }
+
+    fn track_transaction(&mut self, tx_id: u32) {
+        let evicted = match &mut self.tx_recency {
+            Some(recency) => recency.push(tx_id, ()),
+            None => return,
+        };
+        // push returns the pushed id itself when it was already tracked
+        if let Some((evicted_id, _)) = evicted.filter(|(evicted_id, _)| *evicted_id != tx_id) {
+            if let Some(evicted_tx) = self.tx_map.remove(&evicted_id) {
+                log::info!(
+                    "tx {} of client {} evicted from the transaction cache, it can no longer be disputed",
+                    evicted_id,
+                    evicted_tx.client
+                );
+            }
+        }
+    }
+
+    // mark a transaction as recently used when a record refers to it
+    fn touch_transaction(&mut self, tx_id: u32) {
+        if let Some(recency) = &mut self.tx_recency {
+            recency.promote(&tx_id);
+        }
+    }
+
+    // in strict mode a record that breaks a processing rule stops the processing, in lenient mode
+    // it is logged, collected in the report and the next record is processed
+    fn skip(&mut self, record: &TransactionEntry, reason: SkipReason) -> Result<(), Error> {
+        let record_error = RecordError {
+            row: self.row,
+            client: record.client,
+            tx: record.tx,
+            reason,
+        };
+
+        match self.config.error_policy {
+            ErrorPolicy::Strict => Err(record_error.into()),
+            ErrorPolicy::Lenient | ErrorPolicy::CollectErrors => {
+                self.record_skipped(record_error);
+                Ok(())
+            }
+        }
+    }
+
+    fn record_skipped(&mut self, record_error: RecordError) {
+        log::warn!("Skipping record: {}", record_error);
+        self.skipped.push(record_error);
+        self.stats.skipped += 1;
+    }
+}
+
+pub fn process_transactions<R>(
+    rdr: &mut Reader<R>,
+    mut raw_record: ByteRecord,
+    processor: &mut TransactionProcessor,
+) -> Result<(), Error>
+where
+    R: io::Read,
+{
+    validate_processor_config(processor.config).map_err(Error::InvalidConfig)?;
+
+    while rdr.read_byte_record(&mut raw_record)? {
+        processor.apply_byte_record(&raw_record, rdr.byte_headers()?)?;
+        processor.print_progress(rdr.position().byte());
+    }
+    Ok(())
+}
+
+// the stages of an active dispute, whose transaction is never evicted by tx_cache_size
+fn is_active_dispute(stage: DisputeStage) -> bool {
+    matches!(stage, DisputeStage::Open | DisputeStage::PendingReview)
+}
+
+// the available balance a client should have, every deposit minus every withdrawal and the funds
+// held by its open disputes. charged back funds are expected to be part of total_withdrawn
+pub fn compute_expected_available<S>(
+    client_id: u16,
+    tx_map: &HashMap<u32, Transaction, S>,
+    total_deposited: Amount,
+    total_withdrawn: Amount,
+) -> Amount {
+    total_deposited - total_withdrawn - open_dispute_amount(client_id, tx_map)
+}
+
+fn open_dispute_amount<S>(client_id: u16, tx_map: &HashMap<u32, Transaction, S>) -> Amount {
+    tx_map
+        .values()
+        .filter(|tx| tx.client == client_id && tx.dispute_stage == DisputeStage::Open)
+        .map(|tx| tx.disputed_amount)
+        .sum()
+}
+
+// the most each client can lose to chargebacks, the amounts of its open disputes and of those
+// pending review, which a chargeback takes from available
+pub fn compute_chargeback_exposure_by_client(
+    tx_map: &HashMap<u32, Transaction>,
+) -> HashMap<u16, Amount> {
+    let mut exposure = HashMap::new();
+    for tx in tx_map.values() {
+        if is_active_dispute(tx.dispute_stage) {
+            *exposure.entry(tx.client).or_insert(Amount::ZERO) += tx.disputed_amount;
+        }
+    }
+    exposure
+}
+
+pub fn compute_total_chargeback_exposure(tx_map: &HashMap<u32, Transaction>) -> Amount {
+    tx_map
+        .values()
+        .filter(|tx| is_active_dispute(tx.dispute_stage))
+        .map(|tx| tx.disputed_amount)
+        .sum()
+}
+
+pub fn summarize_dispute_outcomes(tx_map: &HashMap<u32, Transaction>) -> DisputeOutcomeSummary {
+    let mut summary = DisputeOutcomeSummary::default();
+    let mut total_amount = Amount::ZERO;
+    for tx in tx_map.values() {
+        match tx.dispute_stage {
+            DisputeStage::None | DisputeStage::Reversed => continue,
+            DisputeStage::Open | DisputeStage::PendingReview => summary.still_open += 1,
+            DisputeStage::Resolved => summary.resolved += 1,
+            DisputeStage::ChargeBack => summary.charged_back += 1,
+        }
+        summary.total_disputed += 1;
+        total_amount += tx.disputed_amount;
+        summary.max_dispute_amount = summary.max_dispute_amount.max(tx.disputed_amount);
+    }
+
+    if summary.total_disputed > 0 {
+        let total = Amount::from(summary.total_disputed);
+        summary.resolution_rate = Amount::from(summary.resolved) / total;
+        summary.chargeback_rate = Amount::from(summary.charged_back) / total;
+        summary.avg_dispute_amount = total_amount / total;
+    }
+    summary
+}
+
+// recovery for held balances that drifted, like after loading a partial state or migrating past a
+// bug. held becomes the sum of the open disputes of the client and available the rest of the
+// total. only meant to be run by administrators
+pub fn rebalance_held_from_tx_map(
+    client_info: &mut HashMap<u16, ClientInfo>,
+    tx_map: &HashMap<u32, Transaction>,
+) {
+    for (client_id, funds) in client_info.iter_mut() {
+        // a dispute pending review holds nothing
+        let held = open_dispute_amount(*client_id, tx_map);
+        if held != funds.held {
+            log::warn!(
+                "rebalancing held of client {} from {} to {}",
+                client_id,
+                funds.held,
+                held
+            );
+        }
+        funds.held = held;
+        funds.available = funds.total - held;
+    }
+}
+
+// combine the transactions of two runs, like the states of two instances that processed parts of
+// the same input. ids found in both maps are resolved by the policy
+pub fn merge_tx_maps(
+    a: HashMap<u32, Transaction>,
+    b: HashMap<u32, Transaction>,
+    policy: DuplicateTransactionPolicy,
+) -> Result<HashMap<u32, Transaction>, Error> {
+    let mut merged = a;
+    for (tx_id, b_entry) in b {
+        match merged.entry(tx_id) {
+            Entry::Vacant(entry) => {
+                entry.insert(b_entry);
+            }
+            Entry::Occupied(mut entry) => match policy {
+                DuplicateTransactionPolicy::UseFirst => {}
+                DuplicateTransactionPolicy::UseLatest => {
+                    entry.insert(b_entry);
+                }
+                DuplicateTransactionPolicy::FailFast => {
+                    return Err(Error::ConflictingTransaction {
+                        tx_id,
+                        a_entry: entry.remove(),
+                        b_entry,
+                    });
+                }
+            },
+        }
+    }
+    Ok(merged)
+}
+
+// close the account of a client once it has no held funds and no open disputes, locking it so
+// that no further record is applied to it. closing for ClosureReason::CustomerRequest also
+// requires the whole balance to have been withdrawn
+pub fn account_closure(
+    client_id: u16,
+    reason: ClosureReason,
+    client_info: &mut HashMap<u16, ClientInfo>,
+    tx_map: &HashMap<u32, Transaction>,
+) -> Result<AccountClosureResult, Error> {
+    let funds = client_info
+        .get_mut(&client_id)
+        .ok_or_else(|| Error::UnexpectedError(format!("Client id {} not found", client_id)))?;
+
+    let transactions = || tx_map.values().filter(|tx| tx.client == client_id);
+    let open_disputes = transactions()
+        .filter(|tx| {
+            matches!(
+                tx.dispute_stage,
+                DisputeStage::Open | DisputeStage::PendingReview
+            )
+        })
+        .count();
+    if open_disputes > 0 {
+        return Err(Error::OpenDisputes {
+            client: client_id,
+            count: open_disputes,
+        });
+    }
+    if funds.available != funds.total
+        || (reason.requires_zero_balance() && funds.total != Amount::ZERO)
+    {
+        return Err(Error::AccountNotSettled {
+            client: client_id,
+            held: funds.held,
+            total: funds.total,
+        });
+    }
+
+    funds.locked = true;
+    funds.closure_reason = Some(reason);
+    log::info!(
+        "closed account of client {} for {:?} with balance {}",
+        client_id,
+        reason,
+        funds.total
+    );
+    Ok(AccountClosureResult {
+        client_id,
+        final_balance: funds.total,
+        open_disputes_blocked: transactions().any(|tx| tx.dispute_stage == DisputeStage::None),
+    })
+}
+
+// the number of transactions in every bucket_size wide range of amounts, keyed by the lower bound
+// of the range. a bucket_size that is not positive gives an empty histogram
+pub fn transaction_amount_histogram(
+    tx_map: &HashMap<u32, Transaction>,
+    bucket_size: Amount,
+) -> BTreeMap<Amount, u32> {
+    amount_histogram(tx_map.values(), bucket_size)
+}
+
+pub fn amount_histogram_for_client(
+    client_id: u16,
+    tx_map: &HashMap<u32, Transaction>,
+    bucket_size: Amount,
+) -> BTreeMap<Amount, u32> {
+    amount_histogram(
+        tx_map.values().filter(|tx| tx.client == client_id),
+        bucket_size,
+    )
+}
+
+// the fraction of clients, including the client itself, whose balance is at most the balance of
+// the client, so the richest client is at 1.0 and a client in the top 10% at 0.9 or above
+pub fn client_info_percentile_rank(
+    client_id: u16,
+    field: BalanceField,
+    client_info: &HashMap<u16, ClientInfo>,
+) -> Option<f64> {
+    let balance = field.of(client_info.get(&client_id)?);
+    let at_most = client_info
+        .values()
+        .filter(|info| field.of(info) <= balance)
+        .count();
+    Some(at_most as f64 / client_info.len() as f64)
+}
+
+fn amount_histogram<'a>(
+    transactions: impl Iterator<Item = &'a Transaction>,
+    bucket_size: Amount,
+) -> BTreeMap<Amount, u32> {
+    let mut histogram = BTreeMap::new();
+    if bucket_size <= Amount::ZERO {
+        return histogram;
+    }
+    for tx in transactions {
+        let floor = (tx.amount / bucket_size).floor() * bucket_size;
+        *histogram.entry(floor).or_insert(0) += 1;
+    }
+    histogram
+}
+
+pub fn exceeds_precision(d: Amount, max_dp: u32) -> bool {
+    // trailing zeros do not add precision, 1.10000 is as precise as 1.1
+    d.normalize().scale() > max_dp
+}
+
+// open a dispute on `partial_amount` of a transaction, moving only that part of the funds from
+// available to held. disputing the full amount behaves exactly like a regular dispute
+pub fn apply_dispute_with_partial_amount<S: BuildHasher>(
+    tx_id: u32,
+    partial_amount: Amount,
+    client_info: &mut HashMap<u16, ClientInfo, S>,
+    tx_map: &mut HashMap<u32, Transaction, S>,
+) -> Result<ProcessingEvent, Error> {
+    let tx = match tx_map.get_mut(&tx_id) {
+        Some(tx) => tx,
+        None => {
+            return Err(Error::UnexpectedError(format!(
+                "Transaction id {} not found",
+                tx_id
+            )))
+        }
+    };
+
+    if tx.dispute_stage != DisputeStage::None {
+        return Err(Error::UnexpectedError(format!(
+            "Transaction id {} is already disputed",
+            tx_id
+        )));
+    }
+
+    if partial_amount <= Amount::ZERO || partial_amount > tx.amount {
+        return Err(Error::InvalidDisputeAmount {
+            tx: tx_id,
+            amount: partial_amount,
+        });
+    }
+
+    let client_funds = match client_info.get_mut(&tx.client) {
+        Some(funds) => funds,
+        None => {
+            return Err(Error::UnexpectedError(format!(
+                "Client id {} not found",
+                tx.client
+            )))
+        }
+    };
+
+    tx.dispute_stage = DisputeStage::Open;
+    tx.disputed_amount = partial_amount;
+
+    // decrease the available funds by the disputed amount
+    client_funds.available -= partial_amount;
+    client_funds.held += partial_amount;
+
+    Ok(ProcessingEvent::DisputeOpened {
+        client: tx.client,
+        tx: tx_id,
+        amount: partial_amount,
+    })
+}
@@ -0,0 +1,100 @@
+use crate::{
+    is_blank_record, open_transaction_file, reader_builder, write_client_info_to_writer, Error,
+    ProcessorConfig, TransactionEntry, TransactionEntryOwned, TransactionProcessor,
+    TransactionType,
+};
+use csv::ByteRecord;
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+// the time spent in each stage of processing a file, in milliseconds. transfers, fees and
+// reversals are counted in other_handler_ms
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TimingBreakdown {
+    pub csv_parse_ms: u64,
+    pub deposit_handler_ms: u64,
+    pub withdrawal_handler_ms: u64,
+    pub dispute_handler_ms: u64,
+    pub resolve_handler_ms: u64,
+    pub chargeback_handler_ms: u64,
+    pub other_handler_ms: u64,
+    pub output_write_ms: u64,
+}
+
+// process a file with the default config, timing the parsing of the records, the handler of each
+// transaction type and the serialization of the output, which is written to io::sink. records
+// are applied like those of process_stream, so skip_duplicate_records is ignored
+pub fn process_transactions_timing_breakdown(path: PathBuf) -> Result<TimingBreakdown, Error> {
+    let config = ProcessorConfig::default();
+    let mut processor = TransactionProcessor::new(&config);
+    let mut rdr = reader_builder().from_reader(open_transaction_file(&path)?);
+    let headers = rdr.byte_headers()?.clone();
+    let mut raw_record = ByteRecord::new();
+
+    let mut csv_parse = Duration::ZERO;
+    let mut handlers: HashMap<TransactionType, Duration> = HashMap::new();
+    loop {
+        let started = Instant::now();
+        if !rdr.read_byte_record(&mut raw_record)? {
+            csv_parse += started.elapsed();
+            break;
+        }
+        if is_blank_record(&raw_record) {
+            continue;
+        }
+        let parsed = raw_record
+            .deserialize(Some(&headers))
+            .map(|record: TransactionEntry| {
+                // records of an unknown type are ignored by the processor as well
+                TransactionType::from_bytes(record.tx_type).map(|tx_type| TransactionEntryOwned {
+                    tx_type,
+                    client: record.client,
+                    tx: record.tx,
+                    amount: record.amount,
+                    target: record.target,
+                })
+            });
+        csv_parse += started.elapsed();
+
+        let entry = match parsed {
+            Ok(Some(entry)) => entry,
+            Ok(None) => continue,
+            Err(e) => {
+                processor.fail_row(e.into())?;
+                continue;
+            }
+        };
+        let started = Instant::now();
+        processor.apply_entry(&entry)?;
+        *handlers.entry(entry.tx_type).or_default() += started.elapsed();
+    }
+
+    let report = processor.into_report();
+    let started = Instant::now();
+    write_client_info_to_writer(&report.client_info, io::sink())?;
+    let output_write = started.elapsed();
+
+    let handler_ms = |tx_types: &[TransactionType]| {
+        let total: Duration = tx_types
+            .iter()
+            .filter_map(|tx_type| handlers.get(tx_type))
+            .sum();
+        total.as_millis() as u64
+    };
+    Ok(TimingBreakdown {
+        csv_parse_ms: csv_parse.as_millis() as u64,
+        deposit_handler_ms: handler_ms(&[TransactionType::Deposit]),
+        withdrawal_handler_ms: handler_ms(&[TransactionType::Withdrawal]),
+        dispute_handler_ms: handler_ms(&[TransactionType::Dispute]),
+        resolve_handler_ms: handler_ms(&[TransactionType::Resolve]),
+        chargeback_handler_ms: handler_ms(&[TransactionType::Chargeback]),
+        other_handler_ms: handler_ms(&[
+            TransactionType::Transfer,
+            TransactionType::Fee,
+            TransactionType::Reverse,
+        ]),
+        output_write_ms: output_write.as_millis() as u64,
+    })
+}
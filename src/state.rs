@@ -0,0 +1,174 @@
+use crate::{Amount, ClientInfo, Error, Transaction};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::BuildHasher;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+// the balances of every client and every transaction that can still be disputed
+pub type EngineState = (HashMap<u16, ClientInfo>, HashMap<u32, Transaction>);
+
+// the engine state persisted between runs of a multi file ingestion job
+#[derive(Serialize)]
+struct StateRef<'a> {
+    client_info: &'a HashMap<u16, ClientInfo>,
+    tx_map: &'a HashMap<u32, Transaction>,
+}
+
+#[derive(Deserialize)]
+struct State {
+    client_info: HashMap<u16, ClientInfo>,
+    tx_map: HashMap<u32, Transaction>,
+}
+
+pub fn save_state(
+    client_info: &HashMap<u16, ClientInfo>,
+    tx_map: &HashMap<u32, Transaction>,
+    path: &Path,
+) -> Result<(), Error> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    bincode::serialize_into(
+        &mut writer,
+        &StateRef {
+            client_info,
+            tx_map,
+        },
+    )?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn load_state(path: &Path) -> Result<EngineState, Error> {
+    let reader = BufReader::new(File::open(path)?);
+    let state: State = bincode::deserialize_from(reader)?;
+    Ok((state.client_info, state.tx_map))
+}
+
+// the progress of process_transactions_resumable, the number of rows applied and the state they
+// left behind
+#[derive(Debug, Deserialize)]
+pub struct Checkpoint {
+    pub row: u64,
+    pub client_info: HashMap<u16, ClientInfo>,
+    pub tx_map: HashMap<u32, Transaction>,
+}
+
+#[derive(Serialize)]
+#[serde(bound(serialize = "S: BuildHasher"))]
+struct CheckpointRef<'a, S> {
+    row: u64,
+    client_info: &'a HashMap<u16, ClientInfo, S>,
+    tx_map: &'a HashMap<u32, Transaction, S>,
+}
+
+pub(crate) fn save_checkpoint<S: BuildHasher>(
+    row: u64,
+    client_info: &HashMap<u16, ClientInfo, S>,
+    tx_map: &HashMap<u32, Transaction, S>,
+    path: &Path,
+) -> Result<(), Error> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    bincode::serialize_into(
+        &mut writer,
+        &CheckpointRef {
+            row,
+            client_info,
+            tx_map,
+        },
+    )?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn load_checkpoint(path: &Path) -> Result<Checkpoint, Error> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(bincode::deserialize_from(reader)?)
+}
+
+// the clients that changed between two states, with the whole client on both sides of a
+// modification for change data capture sinks
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ClientInfoDelta {
+    pub added: HashMap<u16, ClientInfo>,
+    // sorted by client id
+    pub removed: Vec<u16>,
+    // the client before and after the change
+    pub modified: HashMap<u16, (ClientInfo, ClientInfo)>,
+}
+
+pub fn export_client_info_delta(
+    before: &HashMap<u16, ClientInfo>,
+    after: &HashMap<u16, ClientInfo>,
+) -> ClientInfoDelta {
+    let mut delta = ClientInfoDelta::default();
+    for (client, info) in after {
+        match before.get(client) {
+            None => {
+                delta.added.insert(*client, info.clone());
+            }
+            Some(previous) if previous != info => {
+                delta
+                    .modified
+                    .insert(*client, (previous.clone(), info.clone()));
+            }
+            Some(_) => {}
+        }
+    }
+    delta.removed = before
+        .keys()
+        .filter(|client| !after.contains_key(client))
+        .copied()
+        .collect();
+    delta.removed.sort_unstable();
+    delta
+}
+
+// a balance of a client that differs between two states, see find_balance_discrepancy
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceDiscrepancy {
+    pub client: u16,
+    pub field: &'static str,
+    pub actual_value: Amount,
+    pub expected_value: Amount,
+    // actual_value - expected_value
+    pub delta: Amount,
+}
+
+// compare the balances of every client in two independently computed states, for regression
+// tests and reconciliation. a client missing from one state counts as having zero balances. the
+// largest differences come first
+pub fn find_balance_discrepancy(
+    actual: &HashMap<u16, ClientInfo>,
+    expected: &HashMap<u16, ClientInfo>,
+) -> Vec<BalanceDiscrepancy> {
+    let empty = ClientInfo::empty();
+    let mut clients: Vec<u16> = actual.keys().chain(expected.keys()).copied().collect();
+    clients.sort_unstable();
+    clients.dedup();
+
+    let mut discrepancies = Vec::new();
+    for client in clients {
+        let actual_info = actual.get(&client).unwrap_or(&empty);
+        let expected_info = expected.get(&client).unwrap_or(&empty);
+        let fields = [
+            ("available", actual_info.available, expected_info.available),
+            ("held", actual_info.held, expected_info.held),
+            ("total", actual_info.total, expected_info.total),
+        ];
+        for (field, actual_value, expected_value) in fields {
+            if actual_value != expected_value {
+                discrepancies.push(BalanceDiscrepancy {
+                    client,
+                    field,
+                    actual_value,
+                    expected_value,
+                    delta: actual_value - expected_value,
+                });
+            }
+        }
+    }
+    // stable, so equal differences stay ordered by client
+    discrepancies.sort_by_key(|discrepancy| std::cmp::Reverse(discrepancy.delta.abs()));
+    discrepancies
+}
@@ -0,0 +1,62 @@
+use crate::{
+    is_blank_record, reader_builder, Error, TransactionEntry, TransactionEntryOwned,
+    TransactionType,
+};
+use std::collections::HashMap;
+use std::io;
+
+// every record of an input in the order it was read, for analysis that does not need the
+// balances. records of an unknown type are left out
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TransactionLog {
+    pub entries: Vec<TransactionEntryOwned>,
+}
+
+impl TransactionLog {
+    pub fn from_reader<R: io::Read>(reader: R) -> Result<Self, Error> {
+        let mut rdr = reader_builder().from_reader(reader);
+        let headers = rdr.byte_headers()?.clone();
+        let mut raw_record = csv::ByteRecord::new();
+
+        let mut entries = Vec::new();
+        while rdr.read_byte_record(&mut raw_record)? {
+            if is_blank_record(&raw_record) {
+                continue;
+            }
+            let record: TransactionEntry = raw_record.deserialize(Some(&headers))?;
+            if let Some(tx_type) = TransactionType::from_bytes(record.tx_type) {
+                entries.push(TransactionEntryOwned {
+                    tx_type,
+                    client: record.client,
+                    tx: record.tx,
+                    amount: record.amount,
+                    target: record.target,
+                });
+            }
+        }
+        Ok(TransactionLog { entries })
+    }
+}
+
+// the number of records of each type sent by every client
+pub fn transaction_frequency_by_client(
+    log: &TransactionLog,
+) -> HashMap<u16, HashMap<TransactionType, u64>> {
+    let mut frequency: HashMap<u16, HashMap<TransactionType, u64>> = HashMap::new();
+    for entry in &log.entries {
+        *frequency
+            .entry(entry.client)
+            .or_default()
+            .entry(entry.tx_type)
+            .or_insert(0) += 1;
+    }
+    frequency
+}
+
+pub fn transaction_frequency_by_type(log: &TransactionLog) -> HashMap<TransactionType, u64> {
+    let mut frequency = HashMap::new();
+    for entry in &log.entries {
+        *frequency.entry(entry.tx_type).or_insert(0) += 1;
+    }
+    frequency
+}
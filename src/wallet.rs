@@ -0,0 +1,37 @@
+use crate::{
+    process_file_into, validate_processor_config, ClientReport, Error, ProcessorConfig,
+    TransactionProcessor,
+};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+// checks a client against an external service, like a KYC or AML provider, before the processor
+// creates it
+pub trait WalletValidator {
+    fn is_valid_client(&self, client_id: u16) -> bool;
+}
+
+// accepts every client
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NullWalletValidator;
+
+impl WalletValidator for NullWalletValidator {
+    fn is_valid_client(&self, _client_id: u16) -> bool {
+        true
+    }
+}
+
+// process a file, skipping the deposits and transfers that would create a client the validator
+// does not accept
+pub fn process_transactions_with_wallet_check(
+    path: PathBuf,
+    wallet_validator: Arc<dyn WalletValidator + Send + Sync>,
+    config: ProcessorConfig,
+) -> Result<ClientReport, Error> {
+    validate_processor_config(&config).map_err(Error::InvalidConfig)?;
+
+    let mut processor = TransactionProcessor::new(&config);
+    processor.set_wallet_validator(wallet_validator);
+    process_file_into(&path, &mut processor)?;
+    Ok(processor.into_report())
+}
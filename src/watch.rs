@@ -0,0 +1,161 @@
+use crate::{
+    reader_builder, validate_processor_config, ClientInfo, Error, TransactionProcessor, UTF8_BOM,
+};
+use csv::ByteRecord;
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+// reads a file that is still being written, each call applying the records appended since the
+// previous one
+#[derive(Debug)]
+pub struct AppendedRecords {
+    path: PathBuf,
+    // the end of the last complete record applied
+    last_byte_offset: u64,
+    headers: Option<ByteRecord>,
+}
+
+impl AppendedRecords {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        AppendedRecords {
+            path: path.into(),
+            last_byte_offset: 0,
+            headers: None,
+        }
+    }
+
+    pub fn last_byte_offset(&self) -> u64 {
+        self.last_byte_offset
+    }
+
+    // apply every complete record appended since the last call and return the clients whose
+    // balances changed, sorted by id. a line that is not terminated yet is left for the next call
+    pub fn read_into(&mut self, processor: &mut TransactionProcessor) -> Result<Vec<u16>, Error> {
+        validate_processor_config(processor.config()).map_err(Error::InvalidConfig)?;
+
+        let mut file = File::open(&self.path)?;
+        if file.metadata()?.len() < self.last_byte_offset {
+            return Err(Error::ReadError(io::Error::other(format!(
+                "{} was truncated while being watched",
+                self.path.display()
+            ))));
+        }
+        let complete_end = match last_line_end(&mut file, self.last_byte_offset)? {
+            Some(end) => end,
+            None => return Ok(Vec::new()),
+        };
+        file.seek(SeekFrom::Start(self.last_byte_offset))?;
+        let mut input = BufReader::new(file.take(complete_end - self.last_byte_offset));
+        if self.last_byte_offset == 0 && input.fill_buf()?.starts_with(UTF8_BOM) {
+            input.consume(UTF8_BOM.len());
+        }
+        self.last_byte_offset = complete_end;
+
+        // the balances of every client touched by a record before the first one was applied. the
+        // merchant can be charged by any chargeback
+        let mut before: HashMap<u16, Option<ClientInfo>> = HashMap::new();
+        if let (Some(_), Some(merchant)) = (
+            processor.config().fee_on_chargeback,
+            processor.config().merchant_client_id,
+        ) {
+            before.insert(merchant, processor.client_info().get(&merchant).cloned());
+        }
+        let mut rdr = reader_builder().has_headers(false).from_reader(input);
+        let mut raw_record = ByteRecord::new();
+        while rdr.read_byte_record(&mut raw_record)? {
+            // the csv reader does not trim records when there is no header row
+            raw_record.trim();
+            let headers = match &self.headers {
+                Some(headers) => headers,
+                None => {
+                    self.headers = Some(raw_record.clone());
+                    continue;
+                }
+            };
+
+            for client in touched_clients(&raw_record, headers) {
+                before
+                    .entry(client)
+                    .or_insert_with(|| processor.client_info().get(&client).cloned());
+            }
+            processor.apply_byte_record(&raw_record, headers)?;
+        }
+
+        let mut changed: Vec<u16> = before
+            .into_iter()
+            .filter(|(client, info)| processor.client_info().get(client) != info.as_ref())
+            .map(|(client, _)| client)
+            .collect();
+        changed.sort_unstable();
+        Ok(changed)
+    }
+}
+
+// the offset just past the last line break after start, found by reading the file backwards so
+// that only the appended records are read forwards
+fn last_line_end(file: &mut File, start: u64) -> io::Result<Option<u64>> {
+    const CHUNK_LEN: u64 = 8 * 1024;
+    let mut chunk = vec![0; CHUNK_LEN as usize];
+    let mut end = file.metadata()?.len();
+    while end > start {
+        let chunk_start = end.saturating_sub(CHUNK_LEN).max(start);
+        let chunk = &mut chunk[..(end - chunk_start) as usize];
+        file.seek(SeekFrom::Start(chunk_start))?;
+        file.read_exact(chunk)?;
+        if let Some(position) = chunk.iter().rposition(|byte| *byte == b'\n') {
+            return Ok(Some(chunk_start + position as u64 + 1));
+        }
+        end = chunk_start;
+    }
+    Ok(None)
+}
+
+// the client of a record and the target of a transfer
+fn touched_clients(raw_record: &ByteRecord, headers: &ByteRecord) -> Vec<u16> {
+    ["client".as_bytes(), b"target"]
+        .iter()
+        .filter_map(|column| headers.iter().position(|header| header == *column))
+        .filter_map(|index| raw_record.get(index))
+        .filter_map(|field| std::str::from_utf8(field).ok()?.parse().ok())
+        .collect()
+}
+
+// process the whole file, then keep applying the records appended to it until the watch fails.
+// on_update is called with the clients whose balances changed, first after the whole file was
+// processed and then after every batch of appended records that changed a balance
+pub fn watch_file<F>(
+    path: &Path,
+    processor: &mut TransactionProcessor,
+    mut on_update: F,
+) -> Result<(), Error>
+where
+    F: FnMut(&TransactionProcessor, &[u16]) -> Result<(), Error>,
+{
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).map_err(io::Error::other)?;
+    // watch before the first pass so that no append is missed
+    watcher
+        .watch(path, RecursiveMode::NonRecursive)
+        .map_err(io::Error::other)?;
+
+    let mut records = AppendedRecords::new(path);
+    let changed = records.read_into(processor)?;
+    on_update(processor, &changed)?;
+
+    for event in receiver {
+        let event = event.map_err(io::Error::other)?;
+        if !matches!(event.kind, EventKind::Modify(_)) {
+            continue;
+        }
+
+        let changed = records.read_into(processor)?;
+        if !changed.is_empty() {
+            on_update(processor, &changed)?;
+        }
+    }
+    Ok(())
+}
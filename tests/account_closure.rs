@@ -0,0 +1,110 @@
+use csv::{ByteRecord, ReaderBuilder};
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    account_closure, process_transactions, process_transactions_from_str, AccountClosureResult,
+    ClosureReason, Error, ProcessorConfig, TransactionProcessor,
+};
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10
+withdrawal,1,2,10
+deposit,2,3,5
+deposit,3,4,8
+dispute,3,4,
+";
+
+#[test]
+fn settled_account_is_closed_and_locked() {
+    let mut report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+
+    let result = account_closure(
+        1,
+        ClosureReason::CustomerRequest,
+        &mut report.client_info,
+        &report.tx_map,
+    )
+    .unwrap();
+
+    assert_eq!(
+        result,
+        AccountClosureResult {
+            client_id: 1,
+            final_balance: dec!(0),
+            open_disputes_blocked: true,
+        }
+    );
+    assert!(report.client_info[&1].locked);
+    assert_eq!(
+        report.client_info[&1].closure_reason,
+        Some(ClosureReason::CustomerRequest)
+    );
+}
+
+#[test]
+fn customer_request_requires_zero_balance() {
+    let mut report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+
+    let result = account_closure(
+        2,
+        ClosureReason::CustomerRequest,
+        &mut report.client_info,
+        &report.tx_map,
+    );
+    assert!(matches!(
+        result,
+        Err(Error::AccountNotSettled { client: 2, .. })
+    ));
+    assert!(!report.client_info[&2].locked);
+
+    let result = account_closure(
+        2,
+        ClosureReason::Fraud,
+        &mut report.client_info,
+        &report.tx_map,
+    )
+    .unwrap();
+    assert_eq!(result.final_balance, dec!(5));
+    assert!(report.client_info[&2].locked);
+}
+
+#[test]
+fn open_disputes_prevent_closure() {
+    let mut report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+
+    let result = account_closure(
+        3,
+        ClosureReason::Regulatory,
+        &mut report.client_info,
+        &report.tx_map,
+    );
+    assert!(matches!(
+        result,
+        Err(Error::OpenDisputes {
+            client: 3,
+            count: 1
+        })
+    ));
+    assert!(!report.client_info[&3].locked);
+    assert_eq!(report.client_info[&3].closure_reason, None);
+}
+
+#[test]
+fn closed_account_ignores_later_records() {
+    let mut report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+    account_closure(
+        2,
+        ClosureReason::Inactivity,
+        &mut report.client_info,
+        &report.tx_map,
+    )
+    .unwrap();
+
+    let config = ProcessorConfig::default();
+    let mut processor =
+        TransactionProcessor::with_state(&config, report.client_info, report.tx_map);
+    let mut rdr =
+        ReaderBuilder::new().from_reader("type,client,tx,amount\ndeposit,2,5,1\n".as_bytes());
+    process_transactions(&mut rdr, ByteRecord::new(), &mut processor).unwrap();
+
+    assert_eq!(processor.client_info()[&2].total, dec!(5));
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "tokio")]
+
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    process_transactions_async, process_transactions_from_str, ProcessorConfig,
+};
+
+const INPUT: &str = "type, client, tx, amount
+deposit, 1, 1, 10.0
+
+deposit, 2, 2, 5.5
+withdrawal, 1, 3, 4.0
+dispute, 2, 2,
+";
+
+#[tokio::test]
+async fn matches_the_synchronous_path() {
+    let config = ProcessorConfig::default();
+    let report = process_transactions_async(INPUT.as_bytes(), &config)
+        .await
+        .unwrap();
+    let expected = process_transactions_from_str(INPUT, &config).unwrap();
+
+    assert_eq!(report.client_info.len(), expected.client_info.len());
+    for (client, info) in &expected.client_info {
+        assert_eq!(report.client_info[client].available, info.available);
+        assert_eq!(report.client_info[client].held, info.held);
+        assert_eq!(report.client_info[client].total, info.total);
+    }
+    assert_eq!(report.client_info[&2].held, dec!(5.5));
+}
+
+#[tokio::test]
+async fn reads_from_a_tokio_file() {
+    let path = std::env::temp_dir().join(format!("async_{}.csv", std::process::id()));
+    tokio::fs::write(&path, INPUT).await.unwrap();
+
+    let file = tokio::fs::File::open(&path).await.unwrap();
+    let report = process_transactions_async(file, &ProcessorConfig::default()).await;
+    tokio::fs::remove_file(&path).await.unwrap();
+
+    assert_eq!(report.unwrap().client_info[&1].available, dec!(6));
+}
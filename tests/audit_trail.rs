@@ -0,0 +1,93 @@
+use std::{env, fs};
+use transaction_processor::{
+    process_transactions_dry_run_with_report, process_transactions_from_str,
+    simulate_batch_outcome, validate_processor_config, ConfigError, ProcessorConfig,
+    TransactionEntryOwned, TransactionType,
+};
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10
+withdrawal,1,2,30
+withdrawal,1,3,4
+dispute,1,1,
+";
+
+#[test]
+fn events_are_appended_on_every_run() {
+    let path = env::temp_dir().join(format!("audit_trail_{}.ndjson", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let config = ProcessorConfig {
+        audit_trail_path: Some(path.clone()),
+        ..Default::default()
+    };
+
+    process_transactions_from_str(INPUT, &config).unwrap();
+    let first_run = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        first_run,
+        r#"{"row":1,"event":"deposit","client":1,"tx":1,"amount":"10"}
+{"row":3,"event":"withdrawal","client":1,"tx":3,"amount":"4"}
+{"row":4,"event":"dispute_opened","client":1,"tx":1,"amount":"10"}
+"#
+    );
+
+    process_transactions_from_str(INPUT, &config).unwrap();
+    let second_run = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(second_run, first_run.repeat(2));
+}
+
+#[test]
+fn directory_is_not_a_valid_audit_trail() {
+    let config = ProcessorConfig {
+        audit_trail_path: Some(env::temp_dir()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        validate_processor_config(&config),
+        Err(vec![ConfigError::InvalidPath {
+            field: "audit_trail_path",
+            path: env::temp_dir(),
+        }])
+    );
+}
+
+#[test]
+fn dry_run_does_not_write_the_audit_trail() {
+    let input = env::temp_dir().join(format!("audit_dry_run_{}.csv", std::process::id()));
+    let path = env::temp_dir().join(format!("audit_dry_run_{}.ndjson", std::process::id()));
+    fs::write(&input, INPUT).unwrap();
+    let config = ProcessorConfig {
+        audit_trail_path: Some(path.clone()),
+        ..Default::default()
+    };
+
+    let report = process_transactions_dry_run_with_report(input.clone(), config);
+    fs::remove_file(&input).unwrap();
+
+    assert_eq!(report.expected_report.stats.deposits, 1);
+    assert!(!path.exists());
+}
+
+#[test]
+fn simulation_does_not_write_the_audit_trail() {
+    let path = env::temp_dir().join(format!("audit_simulation_{}.ndjson", std::process::id()));
+    let config = ProcessorConfig {
+        audit_trail_path: Some(path.clone()),
+        ..Default::default()
+    };
+    let deposit = TransactionEntryOwned {
+        tx_type: TransactionType::Deposit,
+        client: 1,
+        tx: 1,
+        amount: Some(10.into()),
+        target: None,
+    };
+
+    let result = simulate_batch_outcome(&[deposit], &config);
+
+    assert_eq!(result.would_create_clients, vec![1]);
+    assert!(!path.exists());
+}
@@ -0,0 +1,113 @@
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use transaction_processor::{
+    process_transactions_from_str, validate_processor_config, ClientInfo, ConfigError,
+    ProcessorConfig, SkipReason, TransactionEntryOwned, TransactionProcessor, TransactionType,
+};
+
+#[test]
+fn only_the_part_under_the_cap_is_deposited() {
+    let config = ProcessorConfig {
+        client_balance_cap: Some(dec!(1000)),
+        ..Default::default()
+    };
+    let input = "type,client,tx,amount\ndeposit,1,1,900\ndeposit,1,2,1500\ndeposit,1,3,1\n";
+    let report = process_transactions_from_str(input, &config).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(1000));
+    assert_eq!(report.client_info[&1].total, dec!(1000));
+    assert_eq!(
+        report.skipped[0].reason,
+        SkipReason::BalanceCapExceeded {
+            accepted: dec!(100),
+            rejected: dec!(1400),
+        }
+    );
+    assert_eq!(
+        report.skipped[1].reason,
+        SkipReason::BalanceCapExceeded {
+            accepted: dec!(0),
+            rejected: dec!(1),
+        }
+    );
+}
+
+#[test]
+fn fully_rejected_deposit_leaves_no_trace() {
+    let config = ProcessorConfig {
+        client_balance_cap: Some(dec!(1000)),
+        ..Default::default()
+    };
+    let client = ClientInfo {
+        available: dec!(1000),
+        held: dec!(0),
+        total: dec!(1000),
+        locked: false,
+        closure_reason: None,
+    };
+    let mut processor =
+        TransactionProcessor::with_state(&config, HashMap::from([(1, client)]), HashMap::new());
+
+    processor
+        .apply_entry(&TransactionEntryOwned {
+            tx_type: TransactionType::Deposit,
+            client: 1,
+            tx: 1,
+            amount: Some(dec!(5)),
+            target: None,
+        })
+        .unwrap();
+
+    assert!(processor.client_stats().is_empty());
+    assert!(processor.tx_map().is_empty());
+    assert_eq!(processor.client_info()[&1].total, dec!(1000));
+    assert_eq!(
+        processor.skipped()[0].reason,
+        SkipReason::BalanceCapExceeded {
+            accepted: dec!(0),
+            rejected: dec!(5),
+        }
+    );
+}
+
+#[test]
+fn transfer_above_the_cap_is_rejected() {
+    let config = ProcessorConfig {
+        client_balance_cap: Some(dec!(1000)),
+        ..Default::default()
+    };
+    let input = "type,client,tx,amount,target
+deposit,1,1,900
+deposit,2,2,900
+transfer,1,3,900,2
+transfer,1,4,100,2
+";
+    let report = process_transactions_from_str(input, &config).unwrap();
+
+    assert_eq!(report.client_info[&1].total, dec!(800));
+    assert_eq!(report.client_info[&2].total, dec!(1000));
+    assert_eq!(
+        report.skipped[0].reason,
+        SkipReason::BalanceCapExceeded {
+            accepted: dec!(0),
+            rejected: dec!(900),
+        }
+    );
+    assert_eq!(report.skipped.len(), 1);
+}
+
+#[test]
+fn cap_must_be_positive() {
+    let config = ProcessorConfig {
+        client_balance_cap: Some(dec!(-1)),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        validate_processor_config(&config),
+        Err(vec![ConfigError::InvalidDecimalValue {
+            field: "client_balance_cap",
+            value: dec!(-1),
+        }])
+    );
+}
@@ -0,0 +1,69 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    compute_expected_available, ProcessorConfig, TransactionEntryOwned, TransactionProcessor,
+    TransactionType,
+};
+
+fn entry(
+    tx_type: TransactionType,
+    client: u16,
+    tx: u32,
+    amount: Option<Decimal>,
+) -> TransactionEntryOwned {
+    TransactionEntryOwned {
+        tx_type,
+        client,
+        tx,
+        amount,
+        target: None,
+    }
+}
+
+fn apply_all(processor: &mut TransactionProcessor, entries: &[TransactionEntryOwned]) {
+    for entry in entries {
+        processor.apply_entry(entry).unwrap();
+    }
+}
+
+#[test]
+fn expected_available_subtracts_open_disputes() {
+    let config = ProcessorConfig::default();
+    let mut processor = TransactionProcessor::new(&config);
+    apply_all(
+        &mut processor,
+        &[
+            entry(TransactionType::Deposit, 1, 1, Some(dec!(100))),
+            entry(TransactionType::Deposit, 1, 2, Some(dec!(50))),
+            entry(TransactionType::Deposit, 1, 3, Some(dec!(25))),
+            entry(TransactionType::Withdrawal, 1, 4, Some(dec!(30))),
+            entry(TransactionType::Dispute, 1, 2, None),
+            entry(TransactionType::Dispute, 1, 3, Some(dec!(5))),
+        ],
+    );
+
+    let expected = compute_expected_available(1, processor.tx_map(), dec!(175), dec!(30));
+
+    assert_eq!(expected, dec!(90));
+    assert_eq!(processor.client_info()[&1].available, expected);
+    assert!(processor.check_held_balance_consistency().is_empty());
+}
+
+#[test]
+fn balances_stay_consistent_through_resolve_and_chargeback() {
+    let config = ProcessorConfig::default();
+    let mut processor = TransactionProcessor::new(&config);
+    apply_all(
+        &mut processor,
+        &[
+            entry(TransactionType::Deposit, 1, 1, Some(dec!(100))),
+            entry(TransactionType::Deposit, 2, 2, Some(dec!(40))),
+            entry(TransactionType::Dispute, 1, 1, None),
+            entry(TransactionType::Resolve, 1, 1, None),
+            entry(TransactionType::Dispute, 2, 2, None),
+            entry(TransactionType::Chargeback, 2, 2, None),
+        ],
+    );
+
+    assert!(processor.check_held_balance_consistency().is_empty());
+}
@@ -0,0 +1,76 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use transaction_processor::{find_balance_discrepancy, BalanceDiscrepancy, ClientInfo};
+
+fn client(available: Decimal, held: Decimal) -> ClientInfo {
+    ClientInfo {
+        available,
+        held,
+        total: available + held,
+        locked: false,
+        closure_reason: None,
+    }
+}
+
+#[test]
+fn small_difference_in_available_is_found() {
+    let expected = HashMap::from([
+        (1, client(dec!(10), dec!(0))),
+        (2, client(dec!(3), dec!(1))),
+    ]);
+    let mut actual = expected.clone();
+    actual.insert(1, client(dec!(10.01), dec!(0)));
+
+    assert_eq!(
+        find_balance_discrepancy(&actual, &expected),
+        vec![
+            BalanceDiscrepancy {
+                client: 1,
+                field: "available",
+                actual_value: dec!(10.01),
+                expected_value: dec!(10),
+                delta: dec!(0.01),
+            },
+            BalanceDiscrepancy {
+                client: 1,
+                field: "total",
+                actual_value: dec!(10.01),
+                expected_value: dec!(10),
+                delta: dec!(0.01),
+            },
+        ]
+    );
+}
+
+#[test]
+fn largest_discrepancies_come_first() {
+    let expected = HashMap::from([
+        (1, client(dec!(10), dec!(0))),
+        (2, client(dec!(5), dec!(5))),
+    ]);
+    let actual = HashMap::from([(1, client(dec!(9), dec!(0))), (3, client(dec!(2), dec!(0)))]);
+
+    let deltas: Vec<(u16, &str, Decimal)> = find_balance_discrepancy(&actual, &expected)
+        .into_iter()
+        .map(|discrepancy| (discrepancy.client, discrepancy.field, discrepancy.delta))
+        .collect();
+    assert_eq!(
+        deltas,
+        vec![
+            (2, "total", dec!(-10)),
+            (2, "available", dec!(-5)),
+            (2, "held", dec!(-5)),
+            (3, "available", dec!(2)),
+            (3, "total", dec!(2)),
+            (1, "available", dec!(-1)),
+            (1, "total", dec!(-1)),
+        ]
+    );
+}
+
+#[test]
+fn equal_states_have_no_discrepancy() {
+    let state = HashMap::from([(1, client(dec!(10), dec!(2)))]);
+    assert!(find_balance_discrepancy(&state, &state).is_empty());
+}
@@ -0,0 +1,57 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    process_transactions_from_str, BelowZeroPolicy, Error, ProcessorConfig,
+};
+
+// the dispute holds the whole deposit although most of it was already withdrawn
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10
+withdrawal,1,2,8
+dispute,1,1,
+deposit,1,3,1
+deposit,2,4,5
+";
+
+fn config(on_balance_below_zero: BelowZeroPolicy) -> ProcessorConfig {
+    ProcessorConfig {
+        on_balance_below_zero,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn warn_keeps_the_negative_balance() {
+    let report = process_transactions_from_str(INPUT, &config(BelowZeroPolicy::Warn)).unwrap();
+
+    let info = &report.client_info[&1];
+    assert_eq!(info.available, dec!(-7));
+    assert_eq!(info.held, dec!(10));
+    assert_eq!(info.total, dec!(3));
+    // the deposit raising the balance does not count again
+    assert_eq!(report.stats.negative_balance_count, 1);
+}
+
+#[test]
+fn clamp_sets_available_to_zero() {
+    let report = process_transactions_from_str(INPUT, &config(BelowZeroPolicy::Clamp)).unwrap();
+
+    let info = &report.client_info[&1];
+    assert_eq!(info.available, dec!(1));
+    assert_eq!(info.held, dec!(10));
+    assert_eq!(info.total, dec!(11));
+    assert_eq!(report.stats.negative_balance_count, 1);
+}
+
+#[test]
+fn abort_returns_the_negative_balance() {
+    let result = process_transactions_from_str(INPUT, &config(BelowZeroPolicy::Abort));
+
+    assert!(
+        matches!(
+            result,
+            Err(Error::NegativeBalance { client: 1, amount }) if amount == dec!(-8)
+        ),
+        "{:?}",
+        result
+    );
+}
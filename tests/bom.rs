@@ -0,0 +1,20 @@
+use rust_decimal_macros::dec;
+use std::env;
+use std::fs;
+use transaction_processor::{process_transactions_from_path, ProcessorConfig};
+
+#[test]
+fn parses_rows_of_a_bom_prefixed_file() {
+    let path = env::temp_dir().join(format!("bom_{}.csv", std::process::id()));
+    let mut contents = b"\xEF\xBB\xBF".to_vec();
+    contents.extend_from_slice(b"type,client,tx,amount\ndeposit,1,1,5.0\nwithdrawal,1,2,2.0\n");
+    fs::write(&path, contents).unwrap();
+
+    let report =
+        process_transactions_from_path(path.to_str().unwrap(), &ProcessorConfig::default());
+    fs::remove_file(&path).unwrap();
+
+    let report = report.unwrap();
+    assert_eq!(report.client_info[&1].available, dec!(3));
+    assert_eq!(report.client_info[&1].total, dec!(3));
+}
@@ -0,0 +1,63 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use transaction_processor::{
+    compute_chargeback_exposure_by_client, compute_total_chargeback_exposure, export_risk_summary,
+    process_transactions_from_str, ProcessorConfig,
+};
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,20
+deposit,2,3,5.5
+deposit,3,4,7
+dispute,1,1,
+dispute,1,2,4
+dispute,2,3,
+dispute,3,4,
+resolve,3,4,
+";
+
+#[test]
+fn per_client_exposure_adds_up_to_the_total() {
+    let report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+
+    let by_client = compute_chargeback_exposure_by_client(&report.tx_map);
+    assert_eq!(by_client.len(), 2);
+    assert_eq!(by_client[&1], dec!(14));
+    assert_eq!(by_client[&2], dec!(5.5));
+    assert_eq!(
+        by_client.values().sum::<Decimal>(),
+        compute_total_chargeback_exposure(&report.tx_map)
+    );
+}
+
+#[test]
+fn risk_summary_lists_clients_and_total() {
+    let report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+
+    let mut output = Vec::new();
+    export_risk_summary(&report.tx_map, &mut output).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "client,exposure\n1,14\n2,5.5\ntotal,19.5\n"
+    );
+}
+
+#[test]
+fn disputes_pending_review_are_exposed() {
+    let config = ProcessorConfig {
+        max_dispute_amount: Some(dec!(100)),
+        ..Default::default()
+    };
+    let input = "type,client,tx,amount\ndeposit,1,1,500\ndispute,1,1,\n";
+    let report = process_transactions_from_str(input, &config).unwrap();
+
+    assert_eq!(report.client_info[&1].held, dec!(0));
+    assert_eq!(
+        compute_chargeback_exposure_by_client(&report.tx_map),
+        HashMap::from([(1, dec!(500))])
+    );
+    assert_eq!(compute_total_chargeback_exposure(&report.tx_map), dec!(500));
+}
@@ -0,0 +1,147 @@
+use rust_decimal_macros::dec;
+use std::fmt::Write;
+use transaction_processor::{
+    process_transactions_from_str, validate_processor_config, ConfigError, ErrorPolicy,
+    ProcessorConfig, SkipReason,
+};
+
+const MERCHANT: u16 = 100;
+
+fn config() -> ProcessorConfig {
+    ProcessorConfig {
+        fee_on_chargeback: Some(dec!(1)),
+        merchant_client_id: Some(MERCHANT),
+        ..Default::default()
+    }
+}
+
+// a merchant with 10 and 15 clients that each charge back a deposit
+fn input() -> String {
+    let mut input = format!("type,client,tx,amount\ndeposit,{},1000,10\n", MERCHANT);
+    for client in 1..=15u32 {
+        writeln!(input, "deposit,{},{},5", client, client).unwrap();
+        writeln!(input, "dispute,{},{},", client, client).unwrap();
+        writeln!(input, "chargeback,{},{},", client, client).unwrap();
+    }
+    input
+}
+
+#[test]
+fn fee_is_charged_until_the_merchant_runs_out_of_funds() {
+    let report = process_transactions_from_str(&input(), &config()).unwrap();
+
+    let merchant = &report.client_info[&MERCHANT];
+    assert_eq!(merchant.available, dec!(0));
+    assert_eq!(merchant.total, dec!(0));
+    assert_eq!(report.stats.chargebacks, 15);
+    assert_eq!(report.stats.fees_applied, 10);
+    assert_eq!(report.stats.fees_rejected, 5);
+
+    // every chargeback is applied, paid or not
+    for client in 1..=15 {
+        assert!(report.client_info[&client].locked);
+        assert_eq!(report.client_info[&client].total, dec!(0));
+    }
+
+    let failed: Vec<u32> = report.skipped.iter().map(|skipped| skipped.tx).collect();
+    assert_eq!(failed, vec![11, 12, 13, 14, 15]);
+    assert_eq!(
+        report.skipped[0].reason,
+        SkipReason::MerchantFeeFailed {
+            merchant: MERCHANT,
+            available: dec!(0),
+            fee: dec!(1),
+        }
+    );
+}
+
+#[test]
+fn unpaid_fee_does_not_stop_strict_processing() {
+    let config = ProcessorConfig {
+        error_policy: ErrorPolicy::Strict,
+        ..config()
+    };
+
+    let report = process_transactions_from_str(&input(), &config).unwrap();
+
+    assert_eq!(report.stats.chargebacks, 15);
+    assert_eq!(report.skipped.len(), 5);
+}
+
+#[test]
+fn unknown_merchant_cannot_pay() {
+    let input = "type,client,tx,amount
+deposit,1,1,5
+dispute,1,1,
+chargeback,1,1,
+";
+
+    let report = process_transactions_from_str(input, &config()).unwrap();
+
+    assert!(report.client_info[&1].locked);
+    assert!(!report.client_info.contains_key(&MERCHANT));
+    assert_eq!(
+        report.skipped[0].reason,
+        SkipReason::MerchantFeeFailed {
+            merchant: MERCHANT,
+            available: dec!(0),
+            fee: dec!(1),
+        }
+    );
+}
+
+#[test]
+fn fee_must_be_positive() {
+    let config = ProcessorConfig {
+        fee_on_chargeback: Some(dec!(0)),
+        merchant_client_id: Some(MERCHANT),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        validate_processor_config(&config),
+        Err(vec![ConfigError::InvalidDecimalValue {
+            field: "fee_on_chargeback",
+            value: dec!(0),
+        }])
+    );
+}
+
+#[test]
+fn fee_requires_a_merchant() {
+    let config = ProcessorConfig {
+        fee_on_chargeback: Some(dec!(1)),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        validate_processor_config(&config),
+        Err(vec![ConfigError::MissingRequiredField {
+            field: "merchant_client_id",
+        }])
+    );
+}
+
+#[test]
+fn locked_merchant_is_not_charged() {
+    let input = format!(
+        "type,client,tx,amount
+deposit,{m},1,10
+dispute,{m},1,4
+chargeback,{m},1,
+deposit,1,2,5
+dispute,1,2,
+chargeback,1,2,
+",
+        m = MERCHANT
+    );
+
+    let report = process_transactions_from_str(&input, &config()).unwrap();
+
+    let merchant = &report.client_info[&MERCHANT];
+    assert!(merchant.locked);
+    assert_eq!(merchant.available, dec!(6));
+    assert_eq!(report.stats.fees_applied, 0);
+    assert_eq!(report.stats.fees_rejected, 2);
+    assert!(report.client_info[&1].locked);
+}
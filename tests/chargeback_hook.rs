@@ -0,0 +1,112 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::{env, fs};
+use transaction_processor::{
+    process_transactions_dry_run_with_report, process_transactions_from_str,
+    simulate_batch_outcome, LoggingChargebackNotifier, ProcessorConfig, TransactionEntryOwned,
+    TransactionType,
+};
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10
+deposit,2,2,7
+dispute,1,1,
+dispute,2,2,
+resolve,2,2,
+chargeback,1,1,
+chargeback,2,2,
+";
+
+#[test]
+fn hook_is_called_for_every_applied_chargeback() {
+    let notified: Arc<Mutex<Vec<(u16, u32, Decimal)>>> = Arc::new(Mutex::new(Vec::new()));
+    let notified_by_hook = Arc::clone(&notified);
+    let config = ProcessorConfig {
+        on_chargeback: Some(Box::new(move |client, tx, amount| {
+            notified_by_hook.lock().unwrap().push((client, tx, amount));
+        })),
+        ..Default::default()
+    };
+
+    let report = process_transactions_from_str(INPUT, &config).unwrap();
+
+    assert!(report.client_info[&1].locked);
+    assert_eq!(*notified.lock().unwrap(), vec![(1, 1, dec!(10))]);
+}
+
+#[test]
+fn panicking_hook_does_not_stop_processing() {
+    let config = ProcessorConfig {
+        on_chargeback: Some(Box::new(|_, _, _| panic!("webhook unavailable"))),
+        ..Default::default()
+    };
+
+    let report = process_transactions_from_str(INPUT, &config).unwrap();
+
+    assert!(report.client_info[&1].locked);
+    assert_eq!(report.client_info[&1].total, dec!(0));
+    assert_eq!(report.stats.chargebacks, 1);
+}
+
+#[test]
+fn logging_notifier_can_be_used_as_hook() {
+    let config = ProcessorConfig {
+        on_chargeback: Some(LoggingChargebackNotifier.into()),
+        ..Default::default()
+    };
+
+    let report = process_transactions_from_str(INPUT, &config).unwrap();
+    assert_eq!(report.stats.chargebacks, 1);
+}
+
+fn counting_hooks(calls: &Arc<AtomicUsize>) -> ProcessorConfig {
+    let chargebacks = Arc::clone(calls);
+    let new_clients = Arc::clone(calls);
+    ProcessorConfig {
+        on_chargeback: Some(Box::new(move |_, _, _| {
+            chargebacks.fetch_add(1, Ordering::SeqCst);
+        })),
+        on_new_client: Some(Box::new(move |_| {
+            new_clients.fetch_add(1, Ordering::SeqCst);
+        })),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn hooks_are_not_called_by_a_dry_run() {
+    let path = env::temp_dir().join(format!("hooks_dry_run_{}.csv", std::process::id()));
+    fs::write(&path, INPUT).unwrap();
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let report = process_transactions_dry_run_with_report(path.clone(), counting_hooks(&calls));
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(report.would_lock_accounts, vec![1]);
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn hooks_are_not_called_by_a_simulation() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let entry = |tx_type, amount: Option<i64>| TransactionEntryOwned {
+        tx_type,
+        client: 1,
+        tx: 1,
+        amount: amount.map(Into::into),
+        target: None,
+    };
+    let batch = [
+        entry(TransactionType::Deposit, Some(10)),
+        entry(TransactionType::Dispute, None),
+        entry(TransactionType::Chargeback, None),
+    ];
+
+    let result = simulate_batch_outcome(&batch, &counting_hooks(&calls));
+
+    assert_eq!(result.would_lock_clients, vec![1]);
+    assert_eq!(result.expected_chargeback_count, 1);
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
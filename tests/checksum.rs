@@ -0,0 +1,60 @@
+#![cfg(feature = "checksum")]
+
+use rust_decimal_macros::dec;
+use transaction_processor::{process_transactions_from_str, Error, ProcessorConfig};
+
+const VALID: &str = "type,client,tx,amount,checksum
+deposit,1,1,10,2f9a3707
+withdrawal,1,2,3,8D34E3C0
+dispute,1,1,,bb1fb8da
+";
+
+fn verifying() -> ProcessorConfig {
+    ProcessorConfig {
+        verify_checksums: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn records_with_matching_checksums_are_applied() {
+    let report = process_transactions_from_str(VALID, &verifying()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(-3));
+    assert_eq!(report.client_info[&1].held, dec!(10));
+}
+
+#[test]
+fn mismatching_checksum_fails_with_its_row() {
+    // the amount of the withdrawal was altered after the checksum was computed
+    let input = VALID.replace("withdrawal,1,2,3,", "withdrawal,1,2,4,");
+    match process_transactions_from_str(&input, &verifying()) {
+        Err(Error::ChecksumMismatch {
+            row,
+            expected,
+            computed,
+        }) => {
+            assert_eq!(row, 2);
+            assert_eq!(expected, "8D34E3C0");
+            assert_ne!(computed.to_lowercase(), "8d34e3c0");
+        }
+        other => panic!("expected a checksum mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn missing_checksum_fails() {
+    let input = "type,client,tx,amount\ndeposit,1,1,10\n";
+    assert!(matches!(
+        process_transactions_from_str(input, &verifying()),
+        Err(Error::ChecksumMismatch { row: 1, .. })
+    ));
+}
+
+#[test]
+fn checksums_are_ignored_unless_verified() {
+    let input = VALID.replace("2f9a3707", "00000000");
+    let report = process_transactions_from_str(&input, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].total, dec!(7));
+}
@@ -0,0 +1,51 @@
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use transaction_processor::{export_client_info_delta, ClientInfo, ClientInfoDelta};
+
+fn client(total: i64, locked: bool) -> ClientInfo {
+    ClientInfo {
+        available: Decimal::from(total),
+        held: Decimal::ZERO,
+        total: Decimal::from(total),
+        locked,
+        closure_reason: None,
+    }
+}
+
+#[test]
+fn delta_holds_added_removed_and_modified_clients() {
+    let before = HashMap::from([
+        (1, client(10, false)),
+        (2, client(5, false)),
+        (3, client(7, false)),
+        (4, client(1, false)),
+    ]);
+    let after = HashMap::from([
+        (1, client(10, false)),
+        (2, client(8, false)),
+        (3, client(7, true)),
+        (5, client(2, false)),
+    ]);
+
+    assert_eq!(
+        export_client_info_delta(&before, &after),
+        ClientInfoDelta {
+            added: HashMap::from([(5, client(2, false))]),
+            removed: vec![4],
+            modified: HashMap::from([
+                (2, (client(5, false), client(8, false))),
+                (3, (client(7, false), client(7, true))),
+            ]),
+        }
+    );
+}
+
+#[test]
+fn identical_states_have_an_empty_delta() {
+    let state = HashMap::from([(1, client(10, false))]);
+
+    assert_eq!(
+        export_client_info_delta(&state, &state),
+        ClientInfoDelta::default()
+    );
+}
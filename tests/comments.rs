@@ -0,0 +1,30 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{process_transactions_from_str, ProcessorConfig};
+
+#[test]
+fn blank_and_comment_lines_are_ignored() {
+    let input = "# exported by the nightly job
+type,client,tx,amount
+
+deposit,1,1,10
+   
+# a comment between records
+withdrawal,1,2,4
+\t
+deposit,2,3,1
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(6));
+    assert_eq!(report.client_info[&2].available, dec!(1));
+    assert!(report.skipped.is_empty());
+}
+
+#[test]
+fn mixed_line_endings_are_read() {
+    let input = "type,client,tx,amount\r\ndeposit,1,1,10\ndeposit,1,2,5\r\n\r\nwithdrawal,1,3,1\r";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(14));
+    assert_eq!(report.tx_map.len(), 3);
+}
@@ -0,0 +1,76 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{process_csv_bytes, ProcessorConfig};
+
+const INPUT: &[u8] = b"type,client,tx,amount
+deposit,1,1,10
+withdrawal,1,2,4
+";
+
+#[test]
+fn processes_bytes_in_memory() {
+    let report = process_csv_bytes(INPUT, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(6));
+}
+
+#[test]
+fn unparseable_record_fails() {
+    let result = process_csv_bytes(
+        b"type,client,tx,amount\ndeposit,x,1,1\n",
+        &ProcessorConfig::default(),
+    );
+
+    assert!(result.is_err());
+}
+
+#[cfg(feature = "ffi")]
+mod ffi {
+    use super::INPUT;
+    use std::ptr;
+    use transaction_processor::{
+        process_csv_bytes_ffi, process_csv_bytes_free, FFI_NULL_POINTER, FFI_OK,
+        FFI_PROCESSING_ERROR,
+    };
+
+    fn call(input: &[u8]) -> (i32, String) {
+        let mut out_ptr = ptr::null_mut();
+        let mut out_len = 0;
+        unsafe {
+            let status =
+                process_csv_bytes_ffi(input.as_ptr(), input.len(), &mut out_ptr, &mut out_len);
+            let output =
+                String::from_utf8_lossy(std::slice::from_raw_parts(out_ptr, out_len)).into_owned();
+            process_csv_bytes_free(out_ptr, out_len);
+            (status, output)
+        }
+    }
+
+    #[test]
+    fn writes_the_client_csv() {
+        let (status, output) = call(INPUT);
+
+        assert_eq!(status, FFI_OK);
+        assert_eq!(
+            output,
+            "client,available,held,total,locked\n1,6,0.0,6,false\n"
+        );
+    }
+
+    #[test]
+    fn writes_the_error_message() {
+        let (status, output) = call(b"type,client,tx,amount\ndeposit,x,1,1\n");
+
+        assert_eq!(status, FFI_PROCESSING_ERROR);
+        assert!(!output.is_empty());
+    }
+
+    #[test]
+    fn rejects_null_pointers() {
+        let mut out_len = 0;
+        let status = unsafe {
+            process_csv_bytes_ffi(INPUT.as_ptr(), INPUT.len(), ptr::null_mut(), &mut out_len)
+        };
+
+        assert_eq!(status, FFI_NULL_POINTER);
+    }
+}
@@ -0,0 +1,50 @@
+use rust_decimal_macros::dec;
+use std::collections::HashSet;
+use transaction_processor::{ClientInfo, DisputeStage, Transaction, TransactionType};
+
+#[test]
+fn client_info_is_displayed_on_one_line() {
+    let info = ClientInfo {
+        available: dec!(1.5),
+        held: dec!(2),
+        total: dec!(3.5),
+        locked: true,
+        closure_reason: None,
+    };
+
+    assert_eq!(
+        info.to_string(),
+        "available=1.5 held=2 total=3.5 locked=true"
+    );
+}
+
+#[test]
+fn transaction_is_displayed_on_one_line() {
+    let tx = Transaction {
+        tx_id: 9,
+        tx_type: TransactionType::Deposit,
+        client: 4,
+        amount: dec!(10.25),
+        disputed_amount: dec!(10.25),
+        dispute_stage: DisputeStage::Open,
+        counterparty: None,
+    };
+
+    assert_eq!(tx.to_string(), "tx=9 client=4 amount=10.25 stage=Open");
+}
+
+#[test]
+fn transactions_are_hashed_by_id() {
+    let tx = |tx_id, amount| Transaction {
+        tx_id,
+        tx_type: TransactionType::Deposit,
+        client: 1,
+        amount,
+        disputed_amount: dec!(0),
+        dispute_stage: DisputeStage::None,
+        counterparty: None,
+    };
+
+    let transactions = HashSet::from([tx(1, dec!(5)), tx(2, dec!(5)), tx(1, dec!(5))]);
+    assert_eq!(transactions.len(), 2);
+}
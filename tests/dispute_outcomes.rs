@@ -0,0 +1,69 @@
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use transaction_processor::{
+    process_transactions_from_str, summarize_dispute_outcomes, DisputeOutcomeSummary,
+    ProcessorConfig,
+};
+
+#[test]
+fn half_resolved_half_charged_back() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,2,2,30
+deposit,3,3,20
+deposit,4,4,40
+deposit,4,5,5
+dispute,1,1,
+dispute,2,2,
+dispute,3,3,
+dispute,4,4,
+resolve,1,1,
+resolve,2,2,
+chargeback,3,3,
+chargeback,4,4,
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    let summary = summarize_dispute_outcomes(&report.tx_map);
+
+    assert_eq!(
+        summary,
+        DisputeOutcomeSummary {
+            total_disputed: 4,
+            resolved: 2,
+            charged_back: 2,
+            still_open: 0,
+            resolution_rate: dec!(0.5),
+            chargeback_rate: dec!(0.5),
+            avg_dispute_amount: dec!(25),
+            max_dispute_amount: dec!(40),
+        }
+    );
+}
+
+#[test]
+fn open_disputes_lower_both_rates() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,20
+dispute,1,1,
+dispute,1,2,
+resolve,1,1,
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    let summary = summarize_dispute_outcomes(&report.tx_map);
+
+    assert_eq!(summary.total_disputed, 2);
+    assert_eq!(summary.still_open, 1);
+    assert_eq!(summary.resolution_rate, dec!(0.5));
+    assert_eq!(summary.chargeback_rate, dec!(0));
+}
+
+#[test]
+fn no_disputes() {
+    assert_eq!(
+        summarize_dispute_outcomes(&HashMap::new()),
+        DisputeOutcomeSummary::default()
+    );
+}
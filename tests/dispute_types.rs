@@ -0,0 +1,30 @@
+use rust_decimal_macros::dec;
+use std::collections::HashSet;
+use transaction_processor::{
+    process_transactions_from_str, ProcessorConfig, SkipReason, TransactionType,
+};
+
+#[test]
+fn disputes_of_blocked_types_are_skipped() {
+    let config = ProcessorConfig {
+        disable_dispute_for_types: HashSet::from([TransactionType::Withdrawal]),
+        ..Default::default()
+    };
+    let input = "type,client,tx,amount
+deposit,1,1,10
+withdrawal,1,2,4
+dispute,1,2,
+dispute,1,1,
+";
+    let report = process_transactions_from_str(input, &config).unwrap();
+
+    assert_eq!(report.client_info[&1].held, dec!(10));
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].tx, 2);
+    assert_eq!(
+        report.skipped[0].reason,
+        SkipReason::DisputeNotAllowedForType {
+            tx_type: TransactionType::Withdrawal,
+        }
+    );
+}
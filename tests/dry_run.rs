@@ -0,0 +1,39 @@
+use rust_decimal_macros::dec;
+use std::env;
+use std::fs;
+use transaction_processor::{
+    process_transactions_dry_run_with_report, ProcessorConfig, TransactionType,
+};
+
+#[test]
+fn reports_the_expected_outcome() {
+    let path = env::temp_dir().join(format!("dry_run_{}.csv", std::process::id()));
+    fs::write(
+        &path,
+        "type,client,tx,amount
+deposit,1,1,10
+deposit,2,2,5
+deposit,x,3,5
+withdrawal,2,4,1
+dispute,1,1,
+chargeback,1,1,
+withdrawal,2,5,100
+",
+    )
+    .unwrap();
+
+    let report = process_transactions_dry_run_with_report(path.clone(), ProcessorConfig::default());
+    fs::remove_file(&path).unwrap();
+
+    let rows: Vec<u64> = report
+        .validation_errors
+        .iter()
+        .map(|(row, _)| *row)
+        .collect();
+    assert_eq!(rows, vec![3, 7]);
+    assert_eq!(report.expected_report.client_info[&2].available, dec!(4));
+    assert_eq!(report.transaction_counts[&TransactionType::Deposit], 2);
+    assert_eq!(report.transaction_counts[&TransactionType::Withdrawal], 2);
+    assert_eq!(report.transaction_counts[&TransactionType::Chargeback], 1);
+    assert_eq!(report.would_lock_accounts, vec![1]);
+}
@@ -0,0 +1,77 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    process_transactions_from_str, ErrorPolicy, ProcessorConfig, SkipReason,
+};
+
+fn skipping_duplicates() -> ProcessorConfig {
+    ProcessorConfig {
+        skip_duplicate_records: true,
+        ..Default::default()
+    }
+}
+
+const REPEATED: &str = "type,client,tx,amount
+deposit,1,1,10
+fee,1,2,1
+fee,1,2,1
+dispute,1,1,
+ dispute, 1, 1,
+";
+
+#[test]
+fn repeated_rows_are_skipped() {
+    let report = process_transactions_from_str(REPEATED, &skipping_duplicates()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(-1));
+    assert_eq!(report.client_info[&1].held, dec!(10));
+    assert_eq!(report.stats.fees_applied, 1);
+    assert_eq!(report.stats.disputes, 1);
+
+    let skipped: Vec<(u64, &SkipReason)> = report
+        .skipped
+        .iter()
+        .map(|record_error| (record_error.row, &record_error.reason))
+        .collect();
+    assert_eq!(
+        skipped,
+        vec![
+            (3, &SkipReason::DuplicateRecord),
+            (5, &SkipReason::DuplicateRecord),
+        ]
+    );
+}
+
+#[test]
+fn records_differing_in_any_field_are_applied() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+fee,1,2,1
+fee,1,3,1
+fee,1,3,2
+";
+    let report = process_transactions_from_str(input, &skipping_duplicates()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(6));
+    assert!(report.skipped.is_empty());
+}
+
+#[test]
+fn repeated_rows_do_not_fail_strict_processing() {
+    let config = ProcessorConfig {
+        error_policy: ErrorPolicy::Strict,
+        ..skipping_duplicates()
+    };
+
+    let report = process_transactions_from_str(REPEATED, &config).unwrap();
+
+    assert_eq!(report.stats.fees_applied, 1);
+    assert_eq!(report.skipped.len(), 2);
+}
+
+#[test]
+fn repeated_rows_are_applied_by_default() {
+    let report = process_transactions_from_str(REPEATED, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.stats.fees_applied, 2);
+    assert!(report.skipped.is_empty());
+}
@@ -0,0 +1,67 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    process_transactions_from_str, write_event_log_to_writer, AccountEvent, ProcessorConfig,
+};
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10
+withdrawal,1,2,3
+withdrawal,1,3,30
+dispute,1,1,
+chargeback,1,1,
+";
+
+#[test]
+fn event_log_is_not_recorded_by_default() {
+    let report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+    assert_eq!(report.event_log, None);
+}
+
+#[test]
+fn event_log_records_operations_in_order() {
+    let config = ProcessorConfig {
+        record_event_log: true,
+        ..Default::default()
+    };
+    let report = process_transactions_from_str(INPUT, &config).unwrap();
+
+    assert_eq!(
+        report.event_log.unwrap(),
+        vec![
+            AccountEvent::Deposit {
+                client: 1,
+                tx: 1,
+                amount: dec!(10),
+            },
+            AccountEvent::Withdrawal {
+                client: 1,
+                tx: 2,
+                amount: dec!(3),
+            },
+            AccountEvent::DisputeOpened { client: 1, tx: 1 },
+            AccountEvent::ChargeBack { client: 1, tx: 1 },
+            AccountEvent::AccountLocked { client: 1 },
+        ]
+    );
+}
+
+#[test]
+fn event_log_is_written_as_json_lines() {
+    let config = ProcessorConfig {
+        record_event_log: true,
+        ..Default::default()
+    };
+    let report = process_transactions_from_str(INPUT, &config).unwrap();
+
+    let mut output = Vec::new();
+    write_event_log_to_writer(&report.event_log.unwrap(), &mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        r#"{"event":"deposit","client":1,"tx":1,"amount":"10"}
+{"event":"withdrawal","client":1,"tx":2,"amount":"3"}
+{"event":"dispute_opened","client":1,"tx":1}
+{"event":"charge_back","client":1,"tx":1}
+{"event":"account_locked","client":1}
+"#
+    );
+}
@@ -0,0 +1,64 @@
+use rust_decimal_macros::dec;
+use std::{env, fs};
+use transaction_processor::{
+    process_transactions_collecting_events, process_transactions_with_observer, ProcessingEvent,
+    ProcessorConfig,
+};
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10
+withdrawal,1,2,3
+withdrawal,1,3,30
+dispute,1,1,4
+chargeback,1,1,
+";
+
+#[test]
+fn events_are_collected_in_order() {
+    let path = env::temp_dir().join(format!("events_{}.csv", std::process::id()));
+    fs::write(&path, INPUT).unwrap();
+
+    let (report, events) =
+        process_transactions_collecting_events(path.clone(), ProcessorConfig::default()).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert!(report.client_info[&1].locked);
+    assert_eq!(
+        events,
+        vec![
+            ProcessingEvent::Deposit {
+                client: 1,
+                tx: 1,
+                amount: dec!(10),
+            },
+            ProcessingEvent::Withdrawal {
+                client: 1,
+                tx: 2,
+                amount: dec!(3),
+            },
+            ProcessingEvent::DisputeOpened {
+                client: 1,
+                tx: 1,
+                amount: dec!(4),
+            },
+            ProcessingEvent::ChargeBack {
+                client: 1,
+                tx: 1,
+                amount: dec!(4),
+            },
+        ]
+    );
+}
+
+#[test]
+fn observer_sees_every_event() {
+    let path = env::temp_dir().join(format!("observer_{}.csv", std::process::id()));
+    fs::write(&path, INPUT).unwrap();
+
+    let mut count = 0;
+    process_transactions_with_observer(path.clone(), ProcessorConfig::default(), |_| count += 1)
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(count, 4);
+}
@@ -0,0 +1,41 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{process_transactions_from_str, ProcessorConfig, SkipReason};
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10
+fee,1,2,1.5
+fee,1,3,20
+fee,2,4,1
+dispute,1,2,
+";
+
+#[test]
+fn fee_is_debited_and_not_disputable() {
+    let report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(8.5));
+    assert_eq!(report.client_info[&1].total, dec!(8.5));
+    assert_eq!(report.client_info[&1].held, dec!(0));
+    assert!(!report.tx_map.contains_key(&2));
+    assert!(!report.client_info.contains_key(&2));
+}
+
+#[test]
+fn fee_above_available_is_skipped() {
+    let report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].tx, 3);
+    assert_eq!(
+        report.skipped[0].reason,
+        SkipReason::InsufficientFunds {
+            available: dec!(8.5),
+            requested: dec!(20),
+        }
+    );
+    assert_eq!(report.stats.fees_applied, 1);
+    assert_eq!(report.stats.fees_rejected, 1);
+    assert_eq!(report.stats.skipped, 1);
+    assert_eq!(report.stats.deposits, 1);
+    assert_eq!(report.stats.disputes, 0);
+}
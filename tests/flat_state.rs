@@ -0,0 +1,39 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    flatten_processor_state, process_transactions, write_flat_state_csv, DisputeStage,
+    ProcessorConfig, TransactionProcessor,
+};
+
+#[test]
+fn rows_join_transactions_with_their_client() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,2
+deposit,2,3,7.5
+dispute,1,2,
+";
+    let config = ProcessorConfig::default();
+    let mut processor = TransactionProcessor::new(&config);
+    let mut rdr = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(input.as_bytes());
+    process_transactions(&mut rdr, csv::ByteRecord::new(), &mut processor).unwrap();
+
+    let state = flatten_processor_state(&processor);
+    assert_eq!(state.len(), 3);
+    assert_eq!(state[1].tx_id, 2);
+    assert_eq!(state[1].tx_dispute_stage, DisputeStage::Open);
+    assert_eq!(state[1].client_held, dec!(2));
+
+    let mut output = Vec::new();
+    write_flat_state_csv(&state, &mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "tx_id,client_id,tx_amount,tx_dispute_stage,client_available,client_held,client_total,client_locked
+1,1,10,None,10,2,12,false
+2,1,2,Open,10,2,12,false
+3,2,7.5,None,7.5,0.0,7.5,false
+"
+    );
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use transaction_processor::{
+    transaction_frequency_by_client, transaction_frequency_by_type, TransactionLog, TransactionType,
+};
+
+const FIXTURE: &str = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,5
+withdrawal,1,3,2
+deposit,2,4,8
+dispute,2,4,
+resolve,2,4,
+unknown,3,5,1
+";
+
+#[test]
+fn counts_each_type_per_client() {
+    let log = TransactionLog::from_reader(FIXTURE.as_bytes()).unwrap();
+    let frequency = transaction_frequency_by_client(&log);
+
+    assert_eq!(frequency.len(), 2);
+    assert_eq!(
+        frequency[&1],
+        HashMap::from([
+            (TransactionType::Deposit, 2),
+            (TransactionType::Withdrawal, 1)
+        ])
+    );
+    assert_eq!(
+        frequency[&2],
+        HashMap::from([
+            (TransactionType::Deposit, 1),
+            (TransactionType::Dispute, 1),
+            (TransactionType::Resolve, 1),
+        ])
+    );
+}
+
+#[test]
+fn counts_each_type_overall() {
+    let log = TransactionLog::from_reader(FIXTURE.as_bytes()).unwrap();
+
+    assert_eq!(
+        transaction_frequency_by_type(&log),
+        HashMap::from([
+            (TransactionType::Deposit, 3),
+            (TransactionType::Withdrawal, 1),
+            (TransactionType::Dispute, 1),
+            (TransactionType::Resolve, 1),
+        ])
+    );
+}
+
+#[test]
+fn blank_lines_are_skipped() {
+    let log =
+        TransactionLog::from_reader("type,client,tx,amount\n   \ndeposit,1,1,10\n".as_bytes())
+            .unwrap();
+
+    assert_eq!(log.entries.len(), 1);
+    assert_eq!(log.entries[0].tx_type, TransactionType::Deposit);
+}
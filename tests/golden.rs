@@ -0,0 +1,158 @@
+use std::collections::HashSet;
+use transaction_processor::{
+    process_transactions_from_str, write_client_info_to_writer,
+    write_client_info_with_stats_to_writer, write_selected_client_info_to_writer, ProcessorConfig,
+    SkipReason,
+};
+
+// process the input with the default config and render the sorted client output
+fn output_for(input: &str) -> String {
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+    let mut output = Vec::new();
+    write_client_info_to_writer(&report.client_info, &mut output).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn deposit() {
+    let input = "type,client,tx,amount\ndeposit,1,1,1.5\ndeposit,2,2,2\n";
+    assert_eq!(
+        output_for(input),
+        "client,available,held,total,locked\n1,1.5,0.0,1.5,false\n2,2,0.0,2,false\n"
+    );
+}
+
+#[test]
+fn deposit_and_withdrawal() {
+    let input = "type,client,tx,amount\ndeposit,1,1,10\nwithdrawal,1,2,3.25\n";
+    assert_eq!(
+        output_for(input),
+        "client,available,held,total,locked\n1,6.75,0.0,6.75,false\n"
+    );
+}
+
+#[test]
+fn dispute_and_resolve() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,5
+dispute,1,2,
+resolve,1,2,
+";
+    assert_eq!(
+        output_for(input),
+        "client,available,held,total,locked\n1,15,0,15,false\n"
+    );
+}
+
+#[test]
+fn dispute_and_chargeback_locks_the_account() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,5
+dispute,1,2,
+chargeback,1,2,
+";
+    assert_eq!(
+        output_for(input),
+        "client,available,held,total,locked\n1,10,0,10,true\n"
+    );
+}
+
+#[test]
+fn withdrawal_with_insufficient_funds_is_rejected() {
+    let input = "type,client,tx,amount\ndeposit,1,1,10\nwithdrawal,1,2,10.5\n";
+    assert_eq!(
+        output_for(input),
+        "client,available,held,total,locked\n1,10,0.0,10,false\n"
+    );
+}
+
+#[test]
+fn duplicate_transaction_id_is_ignored() {
+    let input = "type,client,tx,amount\ndeposit,1,1,10\ndeposit,1,1,20\n";
+    assert_eq!(
+        output_for(input),
+        "client,available,held,total,locked\n1,10,0.0,10,false\n"
+    );
+}
+
+#[test]
+fn locked_account_ignores_further_deposits() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+dispute,1,1,
+chargeback,1,1,
+deposit,1,2,5
+";
+    assert_eq!(
+        output_for(input),
+        "client,available,held,total,locked\n1,0,0,0,true\n"
+    );
+}
+
+#[test]
+fn only_selected_clients_are_written() {
+    let input = "type,client,tx,amount\ndeposit,1,1,1\ndeposit,2,2,2\ndeposit,3,3,3\n";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    let mut output = Vec::new();
+    write_selected_client_info_to_writer(&report.client_info, &HashSet::from([3, 1]), &mut output)
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "client,available,held,total,locked\n1,1,0.0,1,false\n3,3,0.0,3,false\n"
+    );
+}
+
+#[test]
+fn dispute_of_another_clients_transaction_is_rejected() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,2,2,3
+dispute,2,1,
+chargeback,2,1,
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    let mut output = Vec::new();
+    write_client_info_to_writer(&report.client_info, &mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "client,available,held,total,locked\n1,10,0.0,10,false\n2,3,0.0,3,false\n"
+    );
+    assert_eq!(report.skipped.len(), 2);
+    assert!(report.skipped.iter().all(|skipped| skipped.client == 2
+        && skipped.tx == 1
+        && skipped.reason == SkipReason::ClientMismatch { tx_client: 1 }));
+}
+
+#[test]
+fn verbose_output_includes_client_activity() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,5
+withdrawal,1,3,4
+withdrawal,1,4,40
+deposit,2,5,1
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    let mut output = Vec::new();
+    write_client_info_with_stats_to_writer(
+        &report.client_info,
+        &report.client_stats,
+        None,
+        &mut output,
+    )
+    .unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "client,available,held,total,locked,deposit_count,withdrawal_count,total_deposited,total_withdrawn
+1,11,0.0,11,false,2,1,15,4
+2,1,0.0,1,false,1,0,1,0
+"
+    );
+}
@@ -0,0 +1,60 @@
+use transaction_processor::{
+    process_transactions_from_str, write_client_info_to_writer, write_transaction_log_to_writer,
+    HashAlgorithm, ProcessorConfig,
+};
+
+const INPUT: &str = "type,client,tx,amount,target
+deposit,1,1,10
+deposit,2,2,20
+deposit,3,3,5.5
+withdrawal,1,4,3
+withdrawal,3,5,10
+transfer,2,6,4,3
+dispute,1,1,
+dispute,2,2,
+resolve,2,2,
+chargeback,1,1,
+fee,3,7,0.5
+";
+
+fn output(hash_algorithm: HashAlgorithm) -> (String, String) {
+    let config = ProcessorConfig {
+        hash_algorithm,
+        ..Default::default()
+    };
+    let report = process_transactions_from_str(INPUT, &config).unwrap();
+
+    let mut clients = Vec::new();
+    write_client_info_to_writer(&report.client_info, &mut clients).unwrap();
+    let mut transactions = Vec::new();
+    write_transaction_log_to_writer(&report.tx_map, &mut transactions).unwrap();
+    (
+        String::from_utf8(clients).unwrap(),
+        String::from_utf8(transactions).unwrap(),
+    )
+}
+
+#[test]
+fn default_algorithm_processes_every_record() {
+    let (clients, _) = output(HashAlgorithm::Default);
+
+    assert_eq!(
+        clients,
+        "client,available,held,total,locked
+1,-3,0,-3,true
+2,16,0,16,false
+3,9.0,0.0,9.0,false
+"
+    );
+}
+
+#[test]
+fn ahash_gives_the_same_output() {
+    assert_eq!(output(HashAlgorithm::AHash), output(HashAlgorithm::Default));
+}
+
+#[cfg(feature = "fxhash")]
+#[test]
+fn fx_gives_the_same_output() {
+    assert_eq!(output(HashAlgorithm::Fx), output(HashAlgorithm::Default));
+}
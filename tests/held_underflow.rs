@@ -0,0 +1,66 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    process_transactions_from_str, Error, ErrorPolicy, ProcessorConfig, TransactionEntryOwned,
+    TransactionProcessor, TransactionType,
+};
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,5
+dispute,1,2,
+";
+
+fn chargeback() -> TransactionEntryOwned {
+    TransactionEntryOwned {
+        tx_type: TransactionType::Chargeback,
+        client: 1,
+        tx: 2,
+        amount: None,
+        target: None,
+    }
+}
+
+// a state whose held funds drifted below the open dispute of 5
+fn drifted_processor(config: &ProcessorConfig) -> TransactionProcessor<'_> {
+    let mut report = process_transactions_from_str(INPUT, config).unwrap();
+    let client = report.client_info.get_mut(&1).unwrap();
+    client.held = dec!(2);
+    client.total = dec!(12);
+    TransactionProcessor::with_state(config, report.client_info, report.tx_map)
+}
+
+#[test]
+fn underflow_fails_in_strict_mode() {
+    let config = ProcessorConfig {
+        error_policy: ErrorPolicy::Strict,
+        ..Default::default()
+    };
+    let mut processor = drifted_processor(&config);
+
+    let result = processor.apply_entry(&chargeback());
+
+    assert!(
+        matches!(&result, Err(Error::UnexpectedError(message)) if message.contains("underflow")),
+        "{:?}",
+        result
+    );
+    let client = &processor.client_info()[&1];
+    assert_eq!(client.held, dec!(2));
+    assert_eq!(client.total, dec!(12));
+    assert!(!client.locked);
+}
+
+#[test]
+fn underflow_clamps_held_in_lenient_mode() {
+    let config = ProcessorConfig::default();
+    let mut processor = drifted_processor(&config);
+
+    processor.apply_entry(&chargeback()).unwrap();
+
+    let client = &processor.client_info()[&1];
+    assert_eq!(client.held, dec!(0));
+    assert_eq!(client.available, dec!(10));
+    assert_eq!(client.total, dec!(7));
+    assert!(client.locked);
+    assert_eq!(processor.stats().chargebacks, 1);
+}
@@ -0,0 +1,43 @@
+use rust_decimal_macros::dec;
+use std::collections::BTreeMap;
+use transaction_processor::{
+    amount_histogram_for_client, process_transactions_from_str, transaction_amount_histogram,
+    ProcessorConfig,
+};
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,0.5
+deposit,1,2,49.99
+deposit,2,3,50
+deposit,2,4,120
+deposit,1,5,149.9999
+deposit,2,6,499
+deposit,1,7,500
+";
+
+#[test]
+fn amounts_are_counted_per_bucket() {
+    let report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(
+        transaction_amount_histogram(&report.tx_map, dec!(50)),
+        BTreeMap::from([
+            (dec!(0), 2),
+            (dec!(50), 1),
+            (dec!(100), 2),
+            (dec!(450), 1),
+            (dec!(500), 1),
+        ])
+    );
+}
+
+#[test]
+fn client_histogram_only_counts_its_transactions() {
+    let report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(
+        amount_histogram_for_client(2, &report.tx_map, dec!(50)),
+        BTreeMap::from([(dec!(50), 1), (dec!(100), 1), (dec!(450), 1)])
+    );
+    assert!(transaction_amount_histogram(&report.tx_map, dec!(0)).is_empty());
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "http")]
+
+use rust_decimal_macros::dec;
+use transaction_processor::{process_transactions_from_url, Error, ProcessorConfig};
+
+const TRANSACTIONS: &str = "type, client, tx, amount
+deposit, 1, 1, 10.0
+deposit, 2, 2, 5.0
+withdrawal, 1, 3, 4.0
+";
+
+#[test]
+fn processes_csv_served_over_http() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/transactions.csv")
+        .with_status(200)
+        .with_body(TRANSACTIONS)
+        .create();
+
+    let url = format!("{}/transactions.csv", server.url());
+    let report = process_transactions_from_url(&url, ProcessorConfig::default()).unwrap();
+
+    mock.assert();
+    assert_eq!(report.client_info.len(), 2);
+    assert_eq!(report.client_info[&1].available, dec!(6));
+    assert_eq!(report.client_info[&2].total, dec!(5));
+}
+
+#[test]
+fn non_success_status_is_a_read_error() {
+    let mut server = mockito::Server::new();
+    server.mock("GET", "/missing.csv").with_status(404).create();
+
+    let url = format!("{}/missing.csv", server.url());
+    let result = process_transactions_from_url(&url, ProcessorConfig::default());
+
+    assert!(matches!(result, Err(Error::ReadError(_))));
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn decodes_gzip_encoded_responses() {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(TRANSACTIONS.as_bytes()).unwrap();
+    let body = encoder.finish().unwrap();
+
+    let mut server = mockito::Server::new();
+    server
+        .mock("GET", "/transactions.csv.gz")
+        .with_status(200)
+        .with_header("content-encoding", "gzip")
+        .with_body(body)
+        .create();
+
+    let url = format!("{}/transactions.csv.gz", server.url());
+    let report = process_transactions_from_url(&url, ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(6));
+}
@@ -0,0 +1,51 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    process_transactions_from_str, Error, ErrorPolicy, ProcessorConfig, RecordError, SkipReason,
+};
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10
+withdrawal,1,2,15
+withdrawal,1,3,4
+";
+
+#[test]
+fn lenient_mode_records_the_skipped_withdrawal() {
+    let report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(6));
+    assert_eq!(
+        report.skipped,
+        vec![RecordError {
+            row: 2,
+            client: 1,
+            tx: 2,
+            reason: SkipReason::InsufficientFunds {
+                available: dec!(10),
+                requested: dec!(15),
+            },
+        }]
+    );
+}
+
+#[test]
+fn strict_mode_returns_insufficient_funds() {
+    let config = ProcessorConfig {
+        error_policy: ErrorPolicy::Strict,
+        ..Default::default()
+    };
+    let result = process_transactions_from_str(INPUT, &config);
+
+    match result {
+        Err(Error::InsufficientFunds {
+            client,
+            available,
+            requested,
+        }) => {
+            assert_eq!(client, 1);
+            assert_eq!(available, dec!(10));
+            assert_eq!(requested, dec!(15));
+        }
+        other => panic!("expected insufficient funds, got {:?}", other),
+    }
+}
@@ -0,0 +1,94 @@
+use rust_decimal_macros::dec;
+use std::{env, fs};
+use transaction_processor::{
+    process_transactions_collecting_events, process_transactions_from_str, DisputeStage,
+    ProcessingEvent, ProcessorConfig,
+};
+
+#[test]
+fn large_dispute_is_pending_review() {
+    let config = ProcessorConfig {
+        max_dispute_amount: Some(dec!(10000)),
+        ..Default::default()
+    };
+    let input = "type,client,tx,amount
+deposit,1,1,15000
+deposit,1,2,500
+dispute,1,1,
+dispute,1,2,
+";
+    let report = process_transactions_from_str(input, &config).unwrap();
+
+    assert_eq!(report.tx_map[&1].dispute_stage, DisputeStage::PendingReview);
+    assert_eq!(report.tx_map[&1].disputed_amount, dec!(15000));
+    assert_eq!(report.tx_map[&2].dispute_stage, DisputeStage::Open);
+    assert_eq!(report.client_info[&1].held, dec!(500));
+    assert_eq!(report.client_info[&1].available, dec!(15000));
+}
+
+fn review_config() -> ProcessorConfig {
+    ProcessorConfig {
+        max_dispute_amount: Some(dec!(10000)),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn pending_review_is_counted_and_emitted() {
+    let input = "type,client,tx,amount
+deposit,1,1,15000
+dispute,1,1,
+";
+    let path = env::temp_dir().join(format!("pending_review_{}.csv", std::process::id()));
+    fs::write(&path, input).unwrap();
+
+    let (report, events) =
+        process_transactions_collecting_events(path.clone(), review_config()).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(report.stats.disputes_pending_review, 1);
+    assert_eq!(report.stats.disputes, 0);
+    assert_eq!(
+        events[1],
+        ProcessingEvent::DisputePendingReview {
+            client: 1,
+            tx: 1,
+            amount: dec!(15000),
+        }
+    );
+}
+
+#[test]
+fn resolve_dismisses_a_pending_review() {
+    let input = "type,client,tx,amount
+deposit,1,1,15000
+dispute,1,1,
+resolve,1,1,
+chargeback,1,1,
+";
+    let report = process_transactions_from_str(input, &review_config()).unwrap();
+
+    assert_eq!(report.tx_map[&1].dispute_stage, DisputeStage::Resolved);
+    assert_eq!(report.client_info[&1].available, dec!(15000));
+    assert_eq!(report.client_info[&1].held, dec!(0));
+    assert!(!report.client_info[&1].locked);
+    assert_eq!(report.stats.resolves, 1);
+}
+
+#[test]
+fn chargeback_of_a_pending_review_takes_the_available_funds() {
+    let input = "type,client,tx,amount
+deposit,1,1,15000
+deposit,1,2,500
+dispute,1,1,
+chargeback,1,1,
+";
+    let report = process_transactions_from_str(input, &review_config()).unwrap();
+
+    assert_eq!(report.tx_map[&1].dispute_stage, DisputeStage::ChargeBack);
+    assert_eq!(report.client_info[&1].available, dec!(500));
+    assert_eq!(report.client_info[&1].held, dec!(0));
+    assert_eq!(report.client_info[&1].total, dec!(500));
+    assert!(report.client_info[&1].locked);
+    assert_eq!(report.stats.chargebacks, 1);
+}
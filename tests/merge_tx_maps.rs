@@ -0,0 +1,73 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use transaction_processor::{
+    merge_tx_maps, DisputeStage, DuplicateTransactionPolicy, Error, Transaction, TransactionType,
+};
+
+fn tx(tx_id: u32, amount: Decimal) -> Transaction {
+    Transaction {
+        tx_id,
+        tx_type: TransactionType::Deposit,
+        client: 1,
+        amount,
+        disputed_amount: amount,
+        dispute_stage: DisputeStage::None,
+        counterparty: None,
+    }
+}
+
+// tx 2 is in both maps with a different amount
+fn maps() -> (HashMap<u32, Transaction>, HashMap<u32, Transaction>) {
+    let a = HashMap::from([(1, tx(1, dec!(1))), (2, tx(2, dec!(2)))]);
+    let b = HashMap::from([(2, tx(2, dec!(20))), (3, tx(3, dec!(3)))]);
+    (a, b)
+}
+
+#[test]
+fn use_first_keeps_the_entry_of_the_first_map() {
+    let (a, b) = maps();
+    let merged = merge_tx_maps(a, b, DuplicateTransactionPolicy::UseFirst).unwrap();
+
+    assert_eq!(merged.len(), 3);
+    assert_eq!(merged[&1].amount, dec!(1));
+    assert_eq!(merged[&2].amount, dec!(2));
+    assert_eq!(merged[&3].amount, dec!(3));
+}
+
+#[test]
+fn use_latest_keeps_the_entry_of_the_second_map() {
+    let (a, b) = maps();
+    let merged = merge_tx_maps(a, b, DuplicateTransactionPolicy::UseLatest).unwrap();
+
+    assert_eq!(merged.len(), 3);
+    assert_eq!(merged[&1].amount, dec!(1));
+    assert_eq!(merged[&2].amount, dec!(20));
+    assert_eq!(merged[&3].amount, dec!(3));
+}
+
+#[test]
+fn fail_fast_returns_both_entries_of_the_conflict() {
+    let (a, b) = maps();
+    match merge_tx_maps(a, b, DuplicateTransactionPolicy::FailFast) {
+        Err(Error::ConflictingTransaction {
+            tx_id,
+            a_entry,
+            b_entry,
+        }) => {
+            assert_eq!(tx_id, 2);
+            assert_eq!(a_entry, tx(2, dec!(2)));
+            assert_eq!(b_entry, tx(2, dec!(20)));
+        }
+        other => panic!("expected a conflict, got {:?}", other),
+    }
+}
+
+#[test]
+fn fail_fast_merges_maps_without_conflicts() {
+    let a = HashMap::from([(1, tx(1, dec!(1)))]);
+    let b = HashMap::from([(3, tx(3, dec!(3)))]);
+    let merged = merge_tx_maps(a, b, DuplicateTransactionPolicy::FailFast).unwrap();
+
+    assert_eq!(merged.len(), 2);
+}
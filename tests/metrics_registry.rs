@@ -0,0 +1,62 @@
+#![cfg(feature = "metrics")]
+
+use metrics::{Key, Label};
+use std::sync::atomic::Ordering;
+use std::{env, fs};
+use transaction_processor::{
+    process_transactions_with_metrics_registry, MetricsRegistry, ProcessorConfig,
+};
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,5
+deposit,2,3,7
+deposit,2,4,1
+deposit,3,5,2
+withdrawal,1,6,3
+dispute,2,3,
+";
+
+fn processed(registry: &MetricsRegistry, tx_type: &'static str) -> Option<u64> {
+    let key = Key::from_parts("transactions_processed", vec![Label::new("type", tx_type)]);
+    registry
+        .get_counter(&key)
+        .map(|counter| counter.load(Ordering::Relaxed))
+}
+
+fn process(name: &str, registry: &MetricsRegistry) {
+    let path = env::temp_dir().join(format!("{}_{}.csv", name, std::process::id()));
+    fs::write(&path, INPUT).unwrap();
+    let report = process_transactions_with_metrics_registry(
+        path.clone(),
+        registry,
+        ProcessorConfig::default(),
+    );
+    fs::remove_file(&path).unwrap();
+    report.unwrap();
+}
+
+#[test]
+fn counts_processed_transactions_by_type() {
+    let registry = MetricsRegistry::atomic();
+
+    process("metrics_by_type", &registry);
+
+    assert_eq!(processed(&registry, "deposit"), Some(5));
+    assert_eq!(processed(&registry, "withdrawal"), Some(1));
+    assert_eq!(processed(&registry, "dispute"), Some(1));
+    assert_eq!(processed(&registry, "chargeback"), None);
+}
+
+#[test]
+fn registries_are_not_shared() {
+    let first = MetricsRegistry::atomic();
+    let second = MetricsRegistry::atomic();
+
+    process("metrics_first", &first);
+    process("metrics_first_again", &first);
+    process("metrics_second", &second);
+
+    assert_eq!(processed(&first, "deposit"), Some(10));
+    assert_eq!(processed(&second, "deposit"), Some(5));
+}
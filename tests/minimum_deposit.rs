@@ -0,0 +1,67 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{process_transactions_from_str, ProcessorConfig, SkipReason};
+
+#[test]
+fn first_deposit_must_reach_the_minimum() {
+    let config = ProcessorConfig {
+        client_creation_requires_minimum_deposit: Some(dec!(10)),
+        ..Default::default()
+    };
+    let input = "type,client,tx,amount
+deposit,1,1,5
+deposit,1,2,10
+deposit,1,3,0.5
+";
+    let report = process_transactions_from_str(input, &config).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(10.5));
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].tx, 1);
+    assert_eq!(
+        report.skipped[0].reason,
+        SkipReason::BelowMinimumInitialDeposit {
+            amount: dec!(5),
+            minimum: dec!(10),
+        }
+    );
+}
+
+#[test]
+fn client_is_not_created_by_a_small_deposit() {
+    let config = ProcessorConfig {
+        client_creation_requires_minimum_deposit: Some(dec!(10)),
+        ..Default::default()
+    };
+    let input = "type,client,tx,amount\ndeposit,1,1,5\n";
+    let report = process_transactions_from_str(input, &config).unwrap();
+
+    assert!(report.client_info.is_empty());
+    assert!(report.tx_map.is_empty());
+}
+
+#[test]
+fn small_transfer_does_not_create_its_target() {
+    let config = ProcessorConfig {
+        client_creation_requires_minimum_deposit: Some(dec!(10)),
+        ..Default::default()
+    };
+    let input = "type,client,tx,amount,target
+deposit,1,1,50
+transfer,1,2,0.0001,2
+transfer,1,3,10,3
+transfer,1,4,0.0001,3
+";
+    let report = process_transactions_from_str(input, &config).unwrap();
+
+    assert!(!report.client_info.contains_key(&2));
+    assert_eq!(report.client_info[&3].total, dec!(10.0001));
+    assert_eq!(report.client_info[&1].total, dec!(39.9999));
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(
+        report.skipped[0].reason,
+        SkipReason::BelowMinimumInitialDeposit {
+            amount: dec!(0.0001),
+            minimum: dec!(10),
+        }
+    );
+}
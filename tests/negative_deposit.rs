@@ -0,0 +1,33 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{process_transactions_from_str, ProcessorConfig, SkipReason};
+
+#[test]
+fn non_positive_deposits_are_skipped() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,-5
+deposit,1,3,0
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(10));
+    assert_eq!(report.client_info[&1].total, dec!(10));
+    assert_eq!(report.stats.deposits, 1);
+    assert_eq!(
+        report.skipped[0].reason,
+        SkipReason::NonPositiveAmount { amount: dec!(-5) }
+    );
+    assert_eq!(
+        report.skipped[1].reason,
+        SkipReason::NonPositiveAmount { amount: dec!(0) }
+    );
+}
+
+#[test]
+fn negative_first_deposit_does_not_create_the_client() {
+    let input = "type,client,tx,amount\ndeposit,1,1,-5\n";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    assert!(report.client_info.is_empty());
+    assert!(report.client_stats.is_empty());
+}
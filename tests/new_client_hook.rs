@@ -0,0 +1,67 @@
+use rust_decimal_macros::dec;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use transaction_processor::{process_transactions_from_str, ProcessorConfig, SkipReason};
+
+#[test]
+fn rejected_and_panicking_clients_are_not_created() {
+    let mut config = ProcessorConfig::default();
+    let reject = Arc::clone(&config.reject_new_client);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let seen_by_hook = Arc::clone(&seen);
+    config.on_new_client = Some(Box::new(move |client| {
+        seen_by_hook.lock().unwrap().push(client);
+        match client {
+            2 => reject.store(true, Ordering::SeqCst),
+            3 => panic!("identity service unavailable"),
+            _ => {}
+        }
+    }));
+
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,5
+deposit,2,3,7
+deposit,3,4,1
+";
+    let report = process_transactions_from_str(input, &config).unwrap();
+
+    assert_eq!(report.client_info.len(), 1);
+    assert_eq!(report.client_info[&1].available, dec!(15));
+    assert_eq!(*seen.lock().unwrap(), vec![1, 2, 3]);
+    assert_eq!(report.skipped.len(), 2);
+    assert!(report
+        .skipped
+        .iter()
+        .all(|skipped| skipped.reason == SkipReason::NewClientRejected));
+}
+
+#[test]
+fn transfer_target_is_checked_by_the_hook() {
+    let mut config = ProcessorConfig::default();
+    let reject = Arc::clone(&config.reject_new_client);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let seen_by_hook = Arc::clone(&seen);
+    config.on_new_client = Some(Box::new(move |client| {
+        seen_by_hook.lock().unwrap().push(client);
+        if client == 2 {
+            reject.store(true, Ordering::SeqCst);
+        }
+    }));
+
+    let input = "type,client,tx,amount,target
+deposit,1,1,10
+transfer,1,2,4,2
+transfer,1,3,40,3
+transfer,1,4,4,3
+transfer,1,5,1,3
+";
+    let report = process_transactions_from_str(input, &config).unwrap();
+
+    assert!(!report.client_info.contains_key(&2));
+    assert_eq!(report.client_info[&3].total, dec!(5));
+    assert_eq!(report.client_info[&1].total, dec!(5));
+    // neither a transfer the sender cannot cover nor a transfer to a known client asks the hook
+    assert_eq!(*seen.lock().unwrap(), vec![1, 2, 3]);
+    assert_eq!(report.skipped[0].reason, SkipReason::NewClientRejected);
+}
@@ -0,0 +1,83 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use transaction_processor::{
+    write_client_info_paginated, write_client_info_to_writer, ClientInfo, PaginationInfo,
+};
+
+// clients 1 to 5, inserted out of order
+fn clients() -> HashMap<u16, ClientInfo> {
+    [3, 1, 5, 2, 4]
+        .into_iter()
+        .map(|client| {
+            let info = ClientInfo {
+                available: Decimal::from(client),
+                held: dec!(0),
+                total: Decimal::from(client),
+                locked: false,
+                closure_reason: None,
+            };
+            (client, info)
+        })
+        .collect()
+}
+
+fn page(client_info: &HashMap<u16, ClientInfo>, page: usize) -> (String, PaginationInfo) {
+    let mut output = Vec::new();
+    let info = write_client_info_paginated(client_info, &mut output, page, 2).unwrap();
+    (String::from_utf8(output).unwrap(), info)
+}
+
+#[test]
+fn first_page_has_the_header_and_lowest_ids() {
+    let (output, info) = page(&clients(), 0);
+
+    assert_eq!(
+        output,
+        "client,available,held,total,locked\n1,1,0,1,false\n2,2,0,2,false\n"
+    );
+    assert_eq!(
+        info,
+        PaginationInfo {
+            page: 0,
+            page_size: 2,
+            total_clients: 5,
+            total_pages: 3,
+            has_next: true,
+            has_prev: false,
+        }
+    );
+}
+
+#[test]
+fn last_page_holds_the_remaining_clients() {
+    let (output, info) = page(&clients(), 2);
+
+    assert_eq!(output, "5,5,0,5,false\n");
+    assert!(!info.has_next);
+    assert!(info.has_prev);
+}
+
+#[test]
+fn last_possible_page_is_empty() {
+    let (output, info) = page(&clients(), usize::MAX);
+
+    assert_eq!(output, "");
+    assert!(!info.has_next);
+    assert!(info.has_prev);
+}
+
+#[test]
+fn pages_put_together_match_the_full_output() {
+    let client_info = clients();
+    let mut full = Vec::new();
+    write_client_info_to_writer(&client_info, &mut full).unwrap();
+
+    let pages: String = (0..3).map(|n| page(&client_info, n).0).collect();
+    assert_eq!(pages, String::from_utf8(full).unwrap());
+}
+
+#[test]
+fn page_size_must_be_positive() {
+    assert!(write_client_info_paginated(&clients(), Vec::new(), 0, 0).is_err());
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "rayon")]
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    process_transactions_from_str, process_transactions_parallel, shard_by_client, Error,
+    ProcessorConfig, TransactionEntryOwned, TransactionType,
+};
+
+fn entry(
+    tx_type: TransactionType,
+    client: u16,
+    tx: u32,
+    amount: Option<Decimal>,
+) -> TransactionEntryOwned {
+    TransactionEntryOwned {
+        tx_type,
+        client,
+        tx,
+        amount,
+        target: None,
+    }
+}
+
+#[test]
+fn matches_sequential_processing() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,2,2,20
+withdrawal,1,3,4
+deposit,3,4,7
+dispute,2,2,
+withdrawal,3,5,8
+chargeback,2,2,
+deposit,2,6,1
+";
+    let entries = vec![
+        entry(TransactionType::Deposit, 1, 1, Some(dec!(10))),
+        entry(TransactionType::Deposit, 2, 2, Some(dec!(20))),
+        entry(TransactionType::Withdrawal, 1, 3, Some(dec!(4))),
+        entry(TransactionType::Deposit, 3, 4, Some(dec!(7))),
+        entry(TransactionType::Dispute, 2, 2, None),
+        entry(TransactionType::Withdrawal, 3, 5, Some(dec!(8))),
+        entry(TransactionType::Chargeback, 2, 2, None),
+        entry(TransactionType::Deposit, 2, 6, Some(dec!(1))),
+    ];
+    let config = ProcessorConfig::default();
+
+    let parallel = process_transactions_parallel(shard_by_client(entries), &config).unwrap();
+    let sequential = process_transactions_from_str(input, &config).unwrap();
+
+    assert_eq!(parallel.len(), sequential.client_info.len());
+    for (client, info) in &sequential.client_info {
+        assert_eq!(parallel[client].available, info.available);
+        assert_eq!(parallel[client].held, info.held);
+        assert_eq!(parallel[client].total, info.total);
+        assert_eq!(parallel[client].locked, info.locked);
+    }
+}
+
+#[test]
+fn client_split_across_shards_is_an_error() {
+    let shards = vec![
+        vec![entry(TransactionType::Deposit, 1, 1, Some(dec!(1)))],
+        vec![entry(TransactionType::Deposit, 1, 2, Some(dec!(1)))],
+    ];
+
+    let result = process_transactions_parallel(shards, &ProcessorConfig::default());
+
+    assert!(matches!(result, Err(Error::UnexpectedError(_))));
+}
+
+#[test]
+fn chargeback_fee_is_rejected() {
+    let shards = vec![
+        vec![entry(TransactionType::Deposit, 100, 1, Some(dec!(10)))],
+        vec![
+            entry(TransactionType::Deposit, 1, 2, Some(dec!(5))),
+            entry(TransactionType::Dispute, 1, 2, None),
+            entry(TransactionType::Chargeback, 1, 2, None),
+        ],
+    ];
+    let config = ProcessorConfig {
+        fee_on_chargeback: Some(dec!(1)),
+        merchant_client_id: Some(100),
+        ..Default::default()
+    };
+
+    let result = process_transactions_parallel(shards, &config);
+
+    assert!(matches!(result, Err(Error::UnexpectedError(_))));
+}
@@ -0,0 +1,86 @@
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use transaction_processor::{
+    apply_dispute_with_partial_amount, process_transactions_from_str, ClientInfo, DisputeStage,
+    Error, ProcessingEvent, ProcessorConfig, Transaction, TransactionType,
+};
+
+fn state() -> (HashMap<u16, ClientInfo>, HashMap<u32, Transaction>) {
+    let client_info = HashMap::from([(
+        1,
+        ClientInfo {
+            available: dec!(100),
+            held: dec!(0),
+            total: dec!(100),
+            locked: false,
+            closure_reason: None,
+        },
+    )]);
+    let tx_map = HashMap::from([(
+        7,
+        Transaction {
+            tx_id: 7,
+            tx_type: TransactionType::Deposit,
+            client: 1,
+            amount: dec!(100),
+            disputed_amount: dec!(0),
+            dispute_stage: DisputeStage::None,
+            counterparty: None,
+        },
+    )]);
+    (client_info, tx_map)
+}
+
+#[test]
+fn holds_only_the_partial_amount() {
+    let (mut client_info, mut tx_map) = state();
+
+    let event =
+        apply_dispute_with_partial_amount(7, dec!(40), &mut client_info, &mut tx_map).unwrap();
+
+    assert_eq!(
+        event,
+        ProcessingEvent::DisputeOpened {
+            client: 1,
+            tx: 7,
+            amount: dec!(40)
+        }
+    );
+    assert_eq!(client_info[&1].available, dec!(60));
+    assert_eq!(client_info[&1].held, dec!(40));
+    assert_eq!(client_info[&1].total, dec!(100));
+    assert_eq!(tx_map[&7].disputed_amount, dec!(40));
+    assert_eq!(tx_map[&7].dispute_stage, DisputeStage::Open);
+}
+
+#[test]
+fn full_amount_behaves_like_a_full_dispute() {
+    let config = ProcessorConfig::default();
+    let full = process_transactions_from_str(
+        "type,client,tx,amount\ndeposit,1,7,100\ndispute,1,7,\n",
+        &config,
+    )
+    .unwrap();
+
+    let (mut client_info, mut tx_map) = state();
+    apply_dispute_with_partial_amount(7, dec!(100), &mut client_info, &mut tx_map).unwrap();
+
+    assert_eq!(client_info[&1].available, full.client_info[&1].available);
+    assert_eq!(client_info[&1].held, full.client_info[&1].held);
+    assert_eq!(client_info[&1].total, full.client_info[&1].total);
+}
+
+#[test]
+fn rejects_amounts_outside_the_transaction() {
+    for amount in [dec!(0), dec!(-1), dec!(100.01)] {
+        let (mut client_info, mut tx_map) = state();
+        let result = apply_dispute_with_partial_amount(7, amount, &mut client_info, &mut tx_map);
+
+        assert!(matches!(
+            result,
+            Err(Error::InvalidDisputeAmount { tx: 7, .. })
+        ));
+        assert_eq!(client_info[&1].held, dec!(0));
+        assert_eq!(tx_map[&7].dispute_stage, DisputeStage::None);
+    }
+}
@@ -0,0 +1,70 @@
+use rust_decimal_macros::dec;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use transaction_processor::{
+    process_transactions_returning_partial_on_error, Error, ProcessorConfig,
+};
+
+fn write_input(name: &str, contents: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("{}_{}.csv", name, std::process::id()));
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn keeps_processing_past_bad_rows() {
+    let path = write_input(
+        "partial",
+        "type,client,tx,amount
+deposit,1,1,10
+deposit,abc,2,5
+withdrawal,1,3,20
+deposit,2,4,not-a-number
+deposit,2,5,3
+",
+    );
+
+    let (report, errors) =
+        process_transactions_returning_partial_on_error(path.clone(), ProcessorConfig::default());
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(report.client_info[&1].total, dec!(10));
+    assert_eq!(report.client_info[&2].total, dec!(3));
+
+    let rows: Vec<u64> = errors.iter().map(|(row, _)| *row).collect();
+    assert_eq!(rows, vec![2, 3, 4]);
+    assert!(matches!(errors[0].1, Error::ParseError(_)));
+    assert!(matches!(
+        errors[1].1,
+        Error::InsufficientFunds { client: 1, .. }
+    ));
+    assert!(matches!(errors[2].1, Error::ParseError(_)));
+}
+
+#[test]
+fn every_row_failing_returns_an_empty_report() {
+    let path = write_input(
+        "all_bad",
+        "type,client,tx,amount\ndeposit,x,1,1\ndeposit,y,2,1\n",
+    );
+
+    let (report, errors) =
+        process_transactions_returning_partial_on_error(path.clone(), ProcessorConfig::default());
+    fs::remove_file(&path).unwrap();
+
+    assert!(report.client_info.is_empty());
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn missing_file_is_reported_on_row_zero() {
+    let path = env::temp_dir().join("does_not_exist_partial.csv");
+
+    let (report, errors) =
+        process_transactions_returning_partial_on_error(path, ProcessorConfig::default());
+
+    assert!(report.client_info.is_empty());
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], (0, Error::ReadError(_))));
+}
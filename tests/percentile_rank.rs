@@ -0,0 +1,65 @@
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use transaction_processor::{client_info_percentile_rank, BalanceField, ClientInfo};
+
+fn client(available: Decimal, held: Decimal) -> ClientInfo {
+    ClientInfo {
+        available,
+        held,
+        total: available + held,
+        locked: false,
+        closure_reason: None,
+    }
+}
+
+// client n has n * 10 available and 100 - n * 10 held
+fn ten_clients() -> HashMap<u16, ClientInfo> {
+    (1..=10u16)
+        .map(|id| {
+            let available = Decimal::from(id * 10);
+            (id, client(available, dec!(100) - available))
+        })
+        .collect()
+}
+
+#[test]
+fn median_client_is_at_half() {
+    let client_info = ten_clients();
+
+    assert_eq!(
+        client_info_percentile_rank(5, BalanceField::Available, &client_info),
+        Some(0.5)
+    );
+    assert_eq!(
+        client_info_percentile_rank(10, BalanceField::Available, &client_info),
+        Some(1.0)
+    );
+    assert_eq!(
+        client_info_percentile_rank(1, BalanceField::Available, &client_info),
+        Some(0.1)
+    );
+}
+
+#[test]
+fn rank_depends_on_the_field() {
+    let client_info = ten_clients();
+
+    assert_eq!(
+        client_info_percentile_rank(1, BalanceField::Held, &client_info),
+        Some(1.0)
+    );
+    // every client has a total of 100
+    assert_eq!(
+        client_info_percentile_rank(1, BalanceField::Total, &client_info),
+        Some(1.0)
+    );
+}
+
+#[test]
+fn unknown_client_has_no_rank() {
+    assert_eq!(
+        client_info_percentile_rank(11, BalanceField::Available, &ten_clients()),
+        None
+    );
+}
@@ -0,0 +1,54 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    exceeds_precision, process_transactions_from_str, Error, ErrorPolicy, ProcessorConfig,
+};
+
+#[test]
+fn four_decimal_places_are_accepted() {
+    assert!(!exceeds_precision(dec!(1.1234), 4));
+    assert!(!exceeds_precision(dec!(10), 4));
+}
+
+#[test]
+fn five_decimal_places_are_rejected() {
+    assert!(exceeds_precision(dec!(1.12345), 4));
+}
+
+#[test]
+fn trailing_zeros_do_not_count() {
+    assert!(!exceeds_precision(dec!(1.10000000), 4));
+    assert!(!exceeds_precision(dec!(2.123400), 4));
+}
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10.5
+deposit,1,2,1.123456789
+withdrawal,1,3,0.00001
+withdrawal,1,4,0.5000
+";
+
+#[test]
+fn lenient_mode_skips_imprecise_amounts() {
+    let report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(10));
+    assert_eq!(report.client_info[&1].total, dec!(10));
+}
+
+#[test]
+fn strict_mode_returns_an_error() {
+    let config = ProcessorConfig {
+        error_policy: ErrorPolicy::Strict,
+        ..Default::default()
+    };
+    let result = process_transactions_from_str(INPUT, &config);
+
+    assert!(matches!(
+        result,
+        Err(Error::PrecisionExceeded {
+            tx: 2,
+            max_dp: 4,
+            ..
+        })
+    ));
+}
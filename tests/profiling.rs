@@ -0,0 +1,46 @@
+#![cfg(feature = "profiling")]
+
+use std::fmt::Write;
+use std::time::Instant;
+use std::{env, fs};
+use transaction_processor::{process_transactions_timing_breakdown, Error};
+
+#[test]
+fn stages_add_up_to_at_most_the_whole_run() {
+    let mut input = String::from("type,client,tx,amount\n");
+    for tx in 1..=20_000u32 {
+        let client = tx % 50;
+        writeln!(input, "deposit,{},{},10", client, tx).unwrap();
+        writeln!(input, "withdrawal,{},{},1", client, tx + 100_000).unwrap();
+        writeln!(input, "dispute,{},{},", client, tx).unwrap();
+        writeln!(input, "resolve,{},{},", client, tx).unwrap();
+    }
+    let path = env::temp_dir().join(format!("timing_{}.csv", std::process::id()));
+    fs::write(&path, input).unwrap();
+
+    let started = Instant::now();
+    let timings = process_transactions_timing_breakdown(path.clone()).unwrap();
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    fs::remove_file(&path).unwrap();
+
+    let stages = timings.csv_parse_ms
+        + timings.deposit_handler_ms
+        + timings.withdrawal_handler_ms
+        + timings.dispute_handler_ms
+        + timings.resolve_handler_ms
+        + timings.chargeback_handler_ms
+        + timings.other_handler_ms
+        + timings.output_write_ms;
+    assert!(stages <= elapsed_ms, "{:?} in {}ms", timings, elapsed_ms);
+    assert_eq!(timings.chargeback_handler_ms, 0);
+    assert_eq!(timings.other_handler_ms, 0);
+}
+
+#[test]
+fn missing_file_is_an_error() {
+    let path = env::temp_dir().join("timing_missing.csv");
+    assert!(matches!(
+        process_transactions_timing_breakdown(path),
+        Err(Error::ReadError(_))
+    ));
+}
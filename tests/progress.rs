@@ -0,0 +1,32 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    process_transactions_from_str, validate_processor_config, ConfigError, ProcessorConfig,
+};
+
+#[test]
+fn progress_does_not_change_the_result() {
+    let config = ProcessorConfig {
+        print_progress_every_n: Some(2),
+        ..Default::default()
+    };
+    let input = "type,client,tx,amount\ndeposit,1,1,5\ndeposit,1,2,5\nwithdrawal,1,3,3\n";
+    let report = process_transactions_from_str(input, &config).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(7));
+}
+
+#[test]
+fn progress_interval_must_not_be_zero() {
+    let config = ProcessorConfig {
+        print_progress_every_n: Some(0),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        validate_processor_config(&config),
+        Err(vec![ConfigError::InvalidSize {
+            field: "print_progress_every_n",
+            value: 0,
+        }])
+    );
+}
@@ -0,0 +1,96 @@
+use csv::{ByteRecord, ReaderBuilder, Trim};
+use proptest::prelude::*;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use transaction_processor::{
+    process_transactions, DisputeStage, ProcessorConfig, TransactionProcessor,
+};
+
+const TYPES: [&str; 5] = ["deposit", "withdrawal", "dispute", "resolve", "chargeback"];
+
+#[derive(Debug, Clone)]
+struct Entry {
+    tx_type: &'static str,
+    client: u16,
+    tx: u32,
+    amount: Decimal,
+}
+
+impl Entry {
+    fn to_csv_row(&self) -> String {
+        match self.tx_type {
+            "deposit" | "withdrawal" => {
+                format!(
+                    "{},{},{},{}",
+                    self.tx_type, self.client, self.tx, self.amount
+                )
+            }
+            _ => format!("{},{},{},", self.tx_type, self.client, self.tx),
+        }
+    }
+}
+
+// few clients and transaction ids so that disputes regularly hit existing transactions
+fn entry() -> impl Strategy<Value = Entry> {
+    (0..TYPES.len(), 1..=4u16, 1..=20u32, 1..=10_000_000i64).prop_map(
+        |(tx_type, client, tx, amount)| Entry {
+            tx_type: TYPES[tx_type],
+            client,
+            tx,
+            amount: Decimal::new(amount, 4),
+        },
+    )
+}
+
+// stages only move forward: None -> Open or PendingReview -> Resolved or ChargeBack, and
+// None -> Reversed
+fn stage_rank(stage: &DisputeStage) -> u8 {
+    match stage {
+        DisputeStage::None => 0,
+        DisputeStage::Open | DisputeStage::PendingReview => 1,
+        DisputeStage::Resolved | DisputeStage::ChargeBack | DisputeStage::Reversed => 2,
+    }
+}
+
+fn apply(processor: &mut TransactionProcessor, entry: &Entry) {
+    let input = format!("type,client,tx,amount\n{}\n", entry.to_csv_row());
+    let mut rdr = ReaderBuilder::new()
+        .trim(Trim::All)
+        .flexible(true)
+        .from_reader(input.as_bytes());
+    process_transactions(&mut rdr, ByteRecord::new(), processor).unwrap();
+}
+
+proptest! {
+    #[test]
+    fn account_invariants_hold(entries in prop::collection::vec(entry(), 1..60)) {
+        let config = ProcessorConfig::default();
+        let mut processor = TransactionProcessor::new(&config);
+        let mut ranks: HashMap<u32, u8> = HashMap::new();
+
+        for entry in &entries {
+            apply(&mut processor, entry);
+
+            for (tx_id, tx) in processor.tx_map() {
+                let rank = stage_rank(&tx.dispute_stage);
+                let previous = ranks.insert(*tx_id, rank).unwrap_or(0);
+                prop_assert!(rank >= previous, "tx {} moved back to {:?}", tx_id, tx.dispute_stage);
+            }
+
+            for (client, info) in processor.client_info() {
+                prop_assert_eq!(info.total, info.available + info.held, "client {}", client);
+                prop_assert!(info.held >= Decimal::ZERO, "client {} held {}", client, info.held);
+
+                // available only drops below zero when a dispute holds funds that were already
+                // withdrawn, or when such a dispute was charged back
+                if info.available < Decimal::ZERO {
+                    let disputed = processor.tx_map().values().any(|tx| {
+                        tx.client == *client
+                            && matches!(tx.dispute_stage, DisputeStage::Open | DisputeStage::ChargeBack)
+                    });
+                    prop_assert!(disputed, "client {} available {}", client, info.available);
+                }
+            }
+        }
+    }
+}
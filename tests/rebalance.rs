@@ -0,0 +1,43 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    process_transactions_from_str, rebalance_held_from_tx_map, ProcessorConfig,
+};
+
+#[test]
+fn held_is_recomputed_from_open_disputes() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,5
+dispute,1,2,
+deposit,2,3,7
+";
+    let mut report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    // simulate balances that drifted
+    let client = report.client_info.get_mut(&1).unwrap();
+    client.held = dec!(1);
+    client.available = dec!(14);
+    report.client_info.get_mut(&2).unwrap().held = dec!(3);
+
+    rebalance_held_from_tx_map(&mut report.client_info, &report.tx_map);
+
+    assert_eq!(report.client_info[&1].held, dec!(5));
+    assert_eq!(report.client_info[&1].available, dec!(10));
+    assert_eq!(report.client_info[&2].held, dec!(0));
+    assert_eq!(report.client_info[&2].available, dec!(7));
+}
+
+#[test]
+fn disputes_pending_review_hold_nothing() {
+    let config = ProcessorConfig {
+        max_dispute_amount: Some(dec!(100)),
+        ..Default::default()
+    };
+    let input = "type,client,tx,amount\ndeposit,1,1,500\ndispute,1,1,\n";
+    let mut report = process_transactions_from_str(input, &config).unwrap();
+
+    rebalance_held_from_tx_map(&mut report.client_info, &report.tx_map);
+
+    assert_eq!(report.client_info[&1].held, dec!(0));
+    assert_eq!(report.client_info[&1].available, dec!(500));
+}
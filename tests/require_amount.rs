@@ -0,0 +1,88 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    process_transactions_from_str, Error, ErrorPolicy, ProcessorConfig, SkipReason,
+};
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,
+dispute,1,1,
+dispute,1,1,4
+resolve,1,1,
+";
+
+#[test]
+fn missing_amounts_are_allowed_by_default() {
+    let report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+
+    // the full deposit is disputed and then resolved
+    assert_eq!(report.client_info[&1].available, dec!(10));
+    assert_eq!(report.stats.disputes, 1);
+    assert_eq!(report.stats.resolves, 1);
+    assert!(report.skipped.is_empty());
+}
+
+#[test]
+fn every_record_needs_an_amount_when_required() {
+    let config = ProcessorConfig {
+        require_amount_for_all_types: true,
+        ..Default::default()
+    };
+    let report = process_transactions_from_str(INPUT, &config).unwrap();
+
+    // only the dispute naming its amount is applied
+    assert_eq!(report.client_info[&1].available, dec!(6));
+    assert_eq!(report.client_info[&1].held, dec!(4));
+    assert_eq!(report.stats.disputes, 1);
+    assert_eq!(report.stats.resolves, 0);
+
+    let skipped: Vec<(u64, &SkipReason)> = report
+        .skipped
+        .iter()
+        .map(|record_error| (record_error.row, &record_error.reason))
+        .collect();
+    assert_eq!(
+        skipped,
+        vec![
+            (2, &SkipReason::MissingAmount),
+            (3, &SkipReason::MissingAmount),
+            (5, &SkipReason::MissingAmount),
+        ]
+    );
+}
+
+#[test]
+fn dispute_amount_above_the_transaction_is_not_applied() {
+    let config = ProcessorConfig {
+        require_amount_for_all_types: true,
+        ..Default::default()
+    };
+    let input = "type,client,tx,amount\ndeposit,1,1,10\ndispute,1,1,40\n";
+    let report = process_transactions_from_str(input, &config).unwrap();
+
+    assert_eq!(report.client_info[&1].held, dec!(0));
+    assert_eq!(report.stats.disputes, 0);
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].row, 2);
+    assert_eq!(
+        report.skipped[0].reason,
+        SkipReason::InvalidDisputeAmount {
+            amount: dec!(40),
+            tx_amount: dec!(10),
+        }
+    );
+}
+
+#[test]
+fn dispute_amount_above_the_transaction_fails_in_strict_mode() {
+    let config = ProcessorConfig {
+        require_amount_for_all_types: true,
+        error_policy: ErrorPolicy::Strict,
+        ..Default::default()
+    };
+    let input = "type,client,tx,amount\ndeposit,1,1,10\ndispute,1,1,40\n";
+
+    let result = process_transactions_from_str(input, &config);
+
+    assert!(matches!(result, Err(Error::Rejected(_))), "{:?}", result);
+}
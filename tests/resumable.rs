@@ -0,0 +1,88 @@
+use rust_decimal_macros::dec;
+use std::path::PathBuf;
+use std::{env, fs};
+use transaction_processor::{
+    load_checkpoint, process_transactions_resumable, ErrorPolicy, ProcessorConfig,
+};
+
+fn temp_path(name: &str, extension: &str) -> PathBuf {
+    env::temp_dir().join(format!("{}_{}.{}", name, std::process::id(), extension))
+}
+
+fn strict() -> ProcessorConfig {
+    ProcessorConfig {
+        error_policy: ErrorPolicy::Strict,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn run_without_checkpoint_writes_the_final_one() {
+    let input = temp_path("resumable_fresh", "csv");
+    let checkpoint = temp_path("resumable_fresh", "checkpoint");
+    fs::write(
+        &input,
+        "type,client,tx,amount\ndeposit,1,1,10\n\nwithdrawal,1,2,4\n",
+    )
+    .unwrap();
+
+    let report =
+        process_transactions_resumable(input.clone(), Some(checkpoint.clone()), strict()).unwrap();
+    let saved = load_checkpoint(&checkpoint).unwrap();
+    fs::remove_file(&input).unwrap();
+    fs::remove_file(&checkpoint).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(6));
+    assert_eq!(saved.row, 2);
+    assert_eq!(saved.client_info[&1].available, dec!(6));
+    assert_eq!(saved.tx_map.len(), 2);
+}
+
+#[test]
+fn failed_run_is_resumed_from_the_failing_row() {
+    let input = temp_path("resumable_failed", "csv");
+    let checkpoint = temp_path("resumable_failed", "checkpoint");
+    fs::write(
+        &input,
+        "type,client,tx,amount
+deposit,1,1,10
+deposit,2,2,5
+withdrawal,1,3,50
+deposit,1,4,1
+",
+    )
+    .unwrap();
+
+    let result = process_transactions_resumable(input.clone(), Some(checkpoint.clone()), strict());
+    assert!(result.is_err());
+    let saved = load_checkpoint(&checkpoint).unwrap();
+    assert_eq!(saved.row, 2);
+    assert_eq!(saved.client_info[&1].available, dec!(10));
+
+    // the failing withdrawal is corrected and the run resumed, the deposits before it are not
+    // applied twice
+    let corrected = fs::read_to_string(&input)
+        .unwrap()
+        .replace("withdrawal,1,3,50", "withdrawal,1,3,5");
+    fs::write(&input, corrected).unwrap();
+    let report =
+        process_transactions_resumable(input.clone(), Some(checkpoint.clone()), strict()).unwrap();
+    let saved = load_checkpoint(&checkpoint).unwrap();
+    fs::remove_file(&input).unwrap();
+    fs::remove_file(&checkpoint).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(6));
+    assert_eq!(report.client_info[&2].available, dec!(5));
+    assert_eq!(saved.row, 4);
+}
+
+#[test]
+fn without_checkpoint_path_the_file_is_processed_once() {
+    let input = temp_path("resumable_none", "csv");
+    fs::write(&input, "type,client,tx,amount\ndeposit,1,1,10\n").unwrap();
+
+    let report = process_transactions_resumable(input.clone(), None, strict()).unwrap();
+    fs::remove_file(&input).unwrap();
+
+    assert_eq!(report.client_info[&1].total, dec!(10));
+}
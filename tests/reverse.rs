@@ -0,0 +1,48 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{process_transactions_from_str, DisputeStage, ProcessorConfig};
+
+#[test]
+fn reversed_deposit_and_withdrawal_undo_their_balance_effect() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,5
+withdrawal,1,3,4
+reverse,1,2,
+reverse,1,3,
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(10));
+    assert_eq!(report.client_info[&1].total, dec!(10));
+    assert_eq!(report.tx_map[&2].dispute_stage, DisputeStage::Reversed);
+    assert_eq!(report.tx_map[&3].dispute_stage, DisputeStage::Reversed);
+    assert_eq!(report.stats.reversals, 2);
+}
+
+#[test]
+fn reversed_transaction_cannot_be_disputed() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+reverse,1,1,
+dispute,1,1,
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(0));
+    assert_eq!(report.client_info[&1].held, dec!(0));
+    assert_eq!(report.tx_map[&1].dispute_stage, DisputeStage::Reversed);
+}
+
+#[test]
+fn disputed_transaction_cannot_be_reversed() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+dispute,1,1,
+reverse,1,1,
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].held, dec!(10));
+    assert_eq!(report.client_info[&1].total, dec!(10));
+    assert_eq!(report.tx_map[&1].dispute_stage, DisputeStage::Open);
+}
@@ -0,0 +1,80 @@
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use transaction_processor::{
+    simulate_batch_outcome, BalanceDelta, ErrorPolicy, ProcessorConfig, SimulationResult,
+    TransactionEntryOwned, TransactionType,
+};
+
+fn entry(
+    tx_type: TransactionType,
+    client: u16,
+    tx: u32,
+    amount: Option<i64>,
+) -> TransactionEntryOwned {
+    TransactionEntryOwned {
+        tx_type,
+        client,
+        tx,
+        amount: amount.map(Into::into),
+        target: None,
+    }
+}
+
+fn batch() -> Vec<TransactionEntryOwned> {
+    vec![
+        entry(TransactionType::Deposit, 1, 1, Some(10)),
+        entry(TransactionType::Deposit, 2, 2, Some(5)),
+        entry(TransactionType::Withdrawal, 2, 3, Some(2)),
+        entry(TransactionType::Dispute, 1, 1, None),
+        entry(TransactionType::Chargeback, 1, 1, None),
+        entry(TransactionType::Deposit, 3, 4, Some(4)),
+        entry(TransactionType::Dispute, 3, 4, None),
+        entry(TransactionType::Withdrawal, 4, 5, Some(1)),
+    ]
+}
+
+#[test]
+fn simulation_reports_the_outcome_of_the_batch() {
+    let result = simulate_batch_outcome(&batch(), &ProcessorConfig::default());
+
+    assert_eq!(
+        result,
+        SimulationResult {
+            would_create_clients: vec![1, 2, 3],
+            would_lock_clients: vec![1],
+            would_affect_balances: HashMap::from([
+                (
+                    2,
+                    BalanceDelta {
+                        available_change: dec!(3),
+                        held_change: dec!(0),
+                        total_change: dec!(3),
+                    }
+                ),
+                (
+                    3,
+                    BalanceDelta {
+                        available_change: dec!(0),
+                        held_change: dec!(4),
+                        total_change: dec!(4),
+                    }
+                ),
+            ]),
+            expected_chargeback_count: 1,
+        }
+    );
+}
+
+#[test]
+fn simulation_stops_at_the_first_error_in_strict_mode() {
+    let config = ProcessorConfig {
+        error_policy: ErrorPolicy::Strict,
+        ..Default::default()
+    };
+    let mut entries = batch();
+    entries.insert(1, entry(TransactionType::Withdrawal, 1, 9, Some(100)));
+
+    let result = simulate_batch_outcome(&entries, &config);
+    assert_eq!(result.would_create_clients, vec![1]);
+    assert_eq!(result.expected_chargeback_count, 0);
+}
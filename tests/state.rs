@@ -0,0 +1,43 @@
+use rust_decimal_macros::dec;
+use std::{env, fs};
+use transaction_processor::{
+    load_state, process_transactions_from_str, save_state, DisputeStage, ProcessorConfig,
+    TransactionEntryOwned, TransactionProcessor, TransactionType,
+};
+
+#[test]
+fn saved_state_is_resumed() {
+    let path = env::temp_dir().join(format!("state_{}.bin", std::process::id()));
+    let input = "type,client,tx,amount\ndeposit,1,1,10.5\ndeposit,2,2,4\ndispute,2,2,\n";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+    save_state(&report.client_info, &report.tx_map, &path).unwrap();
+
+    let (client_info, tx_map) = load_state(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(client_info[&1].available, dec!(10.5));
+    assert_eq!(client_info[&2].held, dec!(4));
+    assert_eq!(tx_map[&2].dispute_stage, DisputeStage::Open);
+
+    // the dispute opened in the first run is charged back in the second one
+    let config = ProcessorConfig::default();
+    let mut processor = TransactionProcessor::with_state(&config, client_info, tx_map);
+    processor
+        .apply_entry(&TransactionEntryOwned {
+            tx_type: TransactionType::Chargeback,
+            client: 2,
+            tx: 2,
+            amount: None,
+            target: None,
+        })
+        .unwrap();
+
+    let report = processor.into_report();
+    assert_eq!(report.client_info[&2].total, dec!(0));
+    assert!(report.client_info[&2].locked);
+}
+
+#[test]
+fn missing_state_file_is_an_error() {
+    let path = env::temp_dir().join("does_not_exist_state.bin");
+    assert!(load_state(&path).is_err());
+}
@@ -0,0 +1,72 @@
+use serde_json::json;
+use std::collections::HashSet;
+use transaction_processor::{
+    process_transactions_from_str, write_client_info_json_to_writer, ProcessorConfig,
+    ProcessorStats,
+};
+
+const INPUT: &str = "type,client,tx,amount
+deposit,1,1,10
+deposit,2,2,5
+withdrawal,1,3,3
+withdrawal,2,4,30
+dispute,1,1,
+resolve,1,1,
+";
+
+#[test]
+fn adding_two_identical_runs_doubles_the_counters() {
+    let stats = process_transactions_from_str(INPUT, &ProcessorConfig::default())
+        .unwrap()
+        .stats;
+
+    let total = stats.clone() + stats;
+
+    assert_eq!(
+        total,
+        ProcessorStats {
+            deposits: 4,
+            withdrawals: 2,
+            disputes: 2,
+            resolves: 2,
+            skipped: 2,
+            negative_balance_count: 2,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn stats_are_serialized_with_the_clients() {
+    let report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+
+    let mut output = Vec::new();
+    write_client_info_json_to_writer(&report.client_info, &report.stats, None, &mut output)
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    assert_eq!(body["clients"][0]["client"], json!(1));
+    assert_eq!(body["clients"][0]["available"], json!("7"));
+    assert_eq!(body["clients"][1]["client"], json!(2));
+    assert_eq!(body["stats"]["deposits"], json!(2));
+    assert_eq!(body["stats"]["skipped"], json!(1));
+}
+
+#[test]
+fn only_selected_clients_are_serialized() {
+    let report = process_transactions_from_str(INPUT, &ProcessorConfig::default()).unwrap();
+    let selected = HashSet::from([2]);
+
+    let mut output = Vec::new();
+    write_client_info_json_to_writer(
+        &report.client_info,
+        &report.stats,
+        Some(&selected),
+        &mut output,
+    )
+    .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    assert_eq!(body["clients"].as_array().unwrap().len(), 1);
+    assert_eq!(body["clients"][0]["client"], json!(2));
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "futures")]
+
+use futures::stream;
+use rust_decimal_macros::dec;
+use std::io;
+use transaction_processor::{
+    process_stream, Error, ErrorPolicy, ProcessorConfig, TransactionEntryOwned, TransactionType,
+};
+
+fn entry(
+    tx_type: TransactionType,
+    client: u16,
+    tx: u32,
+    amount: Option<rust_decimal::Decimal>,
+) -> Result<TransactionEntryOwned, Error> {
+    Ok(TransactionEntryOwned {
+        tx_type,
+        client,
+        tx,
+        amount,
+        target: None,
+    })
+}
+
+#[tokio::test]
+async fn processes_owned_entries_from_a_stream() {
+    let entries = stream::iter(vec![
+        entry(TransactionType::Deposit, 1, 1, Some(dec!(10))),
+        entry(TransactionType::Deposit, 2, 2, Some(dec!(3))),
+        entry(TransactionType::Withdrawal, 1, 3, Some(dec!(2.5))),
+        entry(TransactionType::Dispute, 2, 2, None),
+    ]);
+
+    let report = process_stream(entries, ProcessorConfig::default())
+        .await
+        .unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(7.5));
+    assert_eq!(report.client_info[&2].held, dec!(3));
+}
+
+#[tokio::test]
+async fn stream_errors_follow_the_error_policy() {
+    let entries = || {
+        stream::iter(vec![
+            entry(TransactionType::Deposit, 1, 1, Some(dec!(10))),
+            Err(Error::ReadError(io::Error::other("connection reset"))),
+            entry(TransactionType::Deposit, 1, 2, Some(dec!(5))),
+        ])
+    };
+
+    let result = process_stream(entries(), ProcessorConfig::default()).await;
+    assert!(matches!(result, Err(Error::ReadError(_))));
+
+    let config = ProcessorConfig {
+        error_policy: ErrorPolicy::CollectErrors,
+        ..Default::default()
+    };
+    let report = process_stream(entries(), config).await.unwrap();
+    assert_eq!(report.client_info[&1].total, dec!(15));
+}
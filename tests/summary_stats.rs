@@ -0,0 +1,54 @@
+use transaction_processor::{
+    export_summary_stats_csv, load_summary_stats_csv, process_transactions_from_str,
+    ProcessorConfig, ProcessorStats,
+};
+
+#[test]
+fn stats_are_written_as_a_single_row() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+withdrawal,1,2,3
+withdrawal,1,3,30
+dispute,1,1,
+chargeback,1,1,
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    let mut output = Vec::new();
+    export_summary_stats_csv(&report.stats, &mut output).unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "deposits,withdrawals,transfers,disputes,resolves,chargebacks,reversals,skipped,\
+         fees_applied,fees_rejected,disputes_pending_review,negative_balance_count\n\
+         1,1,0,1,0,1,0,1,0,0,0,1\n"
+    );
+}
+
+#[test]
+fn stats_round_trip() {
+    let stats = ProcessorStats {
+        deposits: 120,
+        withdrawals: 45,
+        transfers: 3,
+        disputes: 7,
+        resolves: 4,
+        chargebacks: 2,
+        reversals: 1,
+        skipped: 9,
+        fees_applied: 11,
+        fees_rejected: 5,
+        disputes_pending_review: 8,
+        negative_balance_count: 6,
+    };
+
+    let mut output = Vec::new();
+    export_summary_stats_csv(&stats, &mut output).unwrap();
+    assert_eq!(load_summary_stats_csv(output.as_slice()).unwrap(), stats);
+}
+
+#[test]
+fn missing_row_is_an_error() {
+    let header = "deposits,withdrawals,transfers,disputes,resolves,chargebacks,reversals,skipped,\
+                  fees_applied,fees_rejected\n";
+    assert!(load_summary_stats_csv(header.as_bytes()).is_err());
+}
@@ -0,0 +1,23 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    generate_csv_template, generate_csv_template_with_optional_columns,
+    process_transactions_from_str, ProcessorConfig,
+};
+
+#[test]
+fn template_is_the_canonical_header() {
+    assert_eq!(generate_csv_template(), "type,client,tx,amount\n");
+    assert_eq!(
+        generate_csv_template_with_optional_columns(),
+        "type,client,tx,amount,description,external_id,currency\n"
+    );
+}
+
+#[test]
+fn optional_columns_are_ignored_while_processing() {
+    let input =
+        generate_csv_template_with_optional_columns() + "deposit,1,1,2.5,salary,abc-123,EUR\n";
+    let report = process_transactions_from_str(&input, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].total, dec!(2.5));
+}
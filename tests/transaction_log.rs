@@ -0,0 +1,29 @@
+use transaction_processor::{
+    process_transactions_from_str, write_transaction_log_to_writer, ProcessorConfig,
+};
+
+#[test]
+fn writes_transactions_sorted_by_id() {
+    let input = "type,client,tx,amount
+deposit,2,5,3.5
+deposit,1,2,10
+withdrawal,1,3,4
+dispute,2,5,
+deposit,1,1,1
+chargeback,2,5,
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    let mut output = Vec::new();
+    write_transaction_log_to_writer(&report.tx_map, &mut output).unwrap();
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "tx,client,amount,dispute_stage
+1,1,1,None
+2,1,10,None
+3,1,4,None
+5,2,3.5,ChargeBack
+"
+    );
+}
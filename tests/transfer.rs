@@ -0,0 +1,123 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    process_transactions_from_str, Error, ErrorPolicy, ProcessorConfig, SkipReason, TransactionType,
+};
+
+#[test]
+fn moves_funds_between_clients() {
+    let input = "type,client,tx,amount,target
+deposit,1,1,10
+deposit,2,2,1
+transfer,1,3,4,2
+transfer,1,4,2.5,3
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(3.5));
+    assert_eq!(report.client_info[&1].total, dec!(3.5));
+    assert_eq!(report.client_info[&2].available, dec!(5));
+    assert_eq!(report.client_info[&2].total, dec!(5));
+    assert_eq!(report.client_info[&3].available, dec!(2.5));
+}
+
+#[test]
+fn insufficient_funds_skips_both_sides() {
+    let input = "type,client,tx,amount,target
+deposit,1,1,10
+deposit,2,2,1
+transfer,1,3,11,2
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].total, dec!(10));
+    assert_eq!(report.client_info[&2].total, dec!(1));
+    assert_eq!(
+        report.skipped[0].reason,
+        SkipReason::InsufficientFunds {
+            available: dec!(10),
+            requested: dec!(11),
+        }
+    );
+}
+
+#[test]
+fn transfer_without_target_is_ignored() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+transfer,1,2,4
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info.len(), 1);
+    assert_eq!(report.client_info[&1].total, dec!(10));
+}
+
+#[test]
+fn negative_amount_is_skipped() {
+    let input = "type,client,tx,amount,target
+deposit,1,1,1
+deposit,2,2,100
+transfer,1,3,-100,2
+withdrawal,1,4,100
+transfer,1,5,0,2
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].total, dec!(1));
+    assert_eq!(report.client_info[&2].total, dec!(100));
+    let reasons: Vec<&SkipReason> = report.skipped.iter().map(|e| &e.reason).collect();
+    assert_eq!(
+        reasons,
+        vec![
+            &SkipReason::NonPositiveAmount { amount: dec!(-100) },
+            &SkipReason::InsufficientFunds {
+                available: dec!(1),
+                requested: dec!(100),
+            },
+            &SkipReason::NonPositiveAmount { amount: dec!(0) },
+        ]
+    );
+}
+
+#[test]
+fn negative_amount_fails_in_strict_mode() {
+    let input = "type,client,tx,amount,target
+deposit,1,1,1
+deposit,2,2,100
+transfer,1,3,-100,2
+";
+    let config = ProcessorConfig {
+        error_policy: ErrorPolicy::Strict,
+        ..Default::default()
+    };
+
+    let result = process_transactions_from_str(input, &config);
+
+    assert!(
+        matches!(&result, Err(Error::Rejected(e)) if e.reason == SkipReason::NonPositiveAmount { amount: dec!(-100) }),
+        "{:?}",
+        result
+    );
+}
+
+#[test]
+fn transfer_cannot_be_disputed() {
+    let input = "type,client,tx,amount,target
+deposit,1,1,100
+transfer,1,2,100,2
+dispute,1,2,
+chargeback,1,2,
+";
+    let report = process_transactions_from_str(input, &ProcessorConfig::default()).unwrap();
+
+    assert_eq!(report.client_info[&1].total, dec!(0));
+    assert_eq!(report.client_info[&1].held, dec!(0));
+    assert!(!report.client_info[&1].locked);
+    assert_eq!(report.client_info[&2].total, dec!(100));
+    assert_eq!(
+        report.skipped[0].reason,
+        SkipReason::DisputeNotAllowedForType {
+            tx_type: TransactionType::Transfer,
+        }
+    );
+}
@@ -0,0 +1,98 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{
+    process_transactions_from_str, validate_processor_config, ConfigError, ProcessorConfig,
+};
+
+fn config() -> ProcessorConfig {
+    ProcessorConfig {
+        tx_cache_size: Some(2),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn evicted_transaction_cannot_be_disputed() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,20
+deposit,1,3,30
+dispute,1,1,
+dispute,1,3,
+";
+    let report = process_transactions_from_str(input, &config()).unwrap();
+
+    assert_eq!(report.tx_map.len(), 2);
+    assert!(!report.tx_map.contains_key(&1));
+    assert_eq!(report.client_info[&1].held, dec!(30));
+    assert_eq!(report.client_info[&1].available, dec!(30));
+}
+
+#[test]
+fn disputed_transaction_stays_in_cache() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+deposit,1,2,20
+dispute,1,1,
+deposit,1,3,30
+resolve,1,1,
+";
+    let report = process_transactions_from_str(input, &config()).unwrap();
+
+    assert!(report.tx_map.contains_key(&1));
+    assert!(!report.tx_map.contains_key(&2));
+    assert_eq!(report.client_info[&1].held, dec!(0));
+    assert_eq!(report.client_info[&1].available, dec!(60));
+}
+
+#[test]
+fn cache_size_must_not_be_zero() {
+    let config = ProcessorConfig {
+        tx_cache_size: Some(0),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        validate_processor_config(&config),
+        Err(vec![ConfigError::InvalidSize {
+            field: "tx_cache_size",
+            value: 0,
+        }])
+    );
+}
+
+#[test]
+fn open_dispute_is_never_evicted() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+dispute,1,1,
+deposit,1,2,20
+deposit,1,3,30
+deposit,1,4,40
+chargeback,1,1,
+";
+    let report = process_transactions_from_str(input, &config()).unwrap();
+
+    assert!(report.tx_map.contains_key(&1));
+    assert_eq!(report.client_info[&1].held, dec!(0));
+    assert_eq!(report.client_info[&1].total, dec!(90));
+    assert!(report.client_info[&1].locked);
+}
+
+#[test]
+fn ended_dispute_can_be_evicted_again() {
+    let input = "type,client,tx,amount
+deposit,1,1,10
+dispute,1,1,
+deposit,1,2,20
+deposit,1,3,30
+resolve,1,1,
+deposit,1,4,40
+deposit,1,5,50
+";
+    let report = process_transactions_from_str(input, &config()).unwrap();
+
+    let mut tx_ids: Vec<u32> = report.tx_map.keys().copied().collect();
+    tx_ids.sort_unstable();
+    assert_eq!(tx_ids, vec![4, 5]);
+    assert_eq!(report.client_info[&1].available, dec!(150));
+}
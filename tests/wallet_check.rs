@@ -0,0 +1,74 @@
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::{env, fs};
+use transaction_processor::{
+    process_transactions_from_str, process_transactions_with_wallet_check, ClientReport,
+    NullWalletValidator, ProcessorConfig, SkipReason, WalletValidator,
+};
+
+const INPUT: &str = "type,client,tx,amount,target
+deposit,1,1,10
+deposit,2,2,10
+transfer,2,3,4,4
+transfer,2,4,3,5
+deposit,2,5,1
+";
+
+// only even client ids pass the check
+struct EvenClients;
+
+impl WalletValidator for EvenClients {
+    fn is_valid_client(&self, client_id: u16) -> bool {
+        client_id.is_multiple_of(2)
+    }
+}
+
+fn process(name: &str, wallet_validator: Arc<dyn WalletValidator + Send + Sync>) -> ClientReport {
+    let path = env::temp_dir().join(format!("{}_{}.csv", name, std::process::id()));
+    fs::write(&path, INPUT).unwrap();
+    let report =
+        process_transactions_with_wallet_check(path.clone(), wallet_validator, Default::default());
+    fs::remove_file(&path).unwrap();
+    report.unwrap()
+}
+
+#[test]
+fn null_validator_accepts_every_client() {
+    let report = process("null_wallet", Arc::new(NullWalletValidator));
+
+    assert_eq!(report.client_info.len(), 4);
+    assert_eq!(report.client_info[&2].available, dec!(4));
+    assert!(report.skipped.is_empty());
+}
+
+#[test]
+fn invalid_wallets_are_not_created() {
+    let report = process("even_wallet", Arc::new(EvenClients));
+
+    let mut clients: Vec<&u16> = report.client_info.keys().collect();
+    clients.sort_unstable();
+    assert_eq!(clients, vec![&2, &4]);
+    assert_eq!(report.client_info[&2].available, dec!(7));
+    assert_eq!(report.client_info[&4].available, dec!(4));
+
+    let skipped: Vec<(u64, &SkipReason)> = report
+        .skipped
+        .iter()
+        .map(|record_error| (record_error.row, &record_error.reason))
+        .collect();
+    assert_eq!(
+        skipped,
+        vec![
+            (1, &SkipReason::InvalidWallet { client: 1 }),
+            (4, &SkipReason::InvalidWallet { client: 5 }),
+        ]
+    );
+}
+
+#[test]
+fn processor_uses_null_validator_by_default() {
+    let config = ProcessorConfig::default();
+    let report = process_transactions_from_str(INPUT, &config).unwrap();
+
+    assert_eq!(report.client_info.len(), 4);
+}
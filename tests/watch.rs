@@ -0,0 +1,139 @@
+#![cfg(feature = "watch")]
+
+use rust_decimal_macros::dec;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+use std::{env, thread};
+use transaction_processor::{
+    watch_file, AppendedRecords, Error, ProcessorConfig, TransactionProcessor,
+};
+
+fn temp_file(name: &str, content: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("{}_{}.csv", name, std::process::id()));
+    fs::write(&path, content).unwrap();
+    path
+}
+
+fn append(path: &PathBuf, content: &str) {
+    let mut file = OpenOptions::new().append(true).open(path).unwrap();
+    file.write_all(content.as_bytes()).unwrap();
+}
+
+#[test]
+fn appended_records_are_applied_incrementally() {
+    let path = temp_file("appended", "type,client,tx,amount\ndeposit,1,1,10\n");
+    let config = ProcessorConfig::default();
+    let mut processor = TransactionProcessor::new(&config);
+    let mut records = AppendedRecords::new(&path);
+
+    assert_eq!(records.read_into(&mut processor).unwrap(), vec![1]);
+    assert_eq!(
+        records.read_into(&mut processor).unwrap(),
+        Vec::<u16>::new()
+    );
+
+    // the unterminated line is only applied once it is complete
+    append(&path, "deposit,2,2,5\nwithdrawal,1,3,");
+    assert_eq!(records.read_into(&mut processor).unwrap(), vec![2]);
+    append(&path, "4\n");
+    assert_eq!(records.read_into(&mut processor).unwrap(), vec![1]);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(processor.client_info()[&1].available, dec!(6));
+    assert_eq!(processor.client_info()[&2].available, dec!(5));
+    assert_eq!(processor.rows(), 3);
+}
+
+#[test]
+fn skipped_records_do_not_change_clients() {
+    let path = temp_file("unchanged", "type,client,tx,amount\ndeposit,1,1,10\n");
+    let config = ProcessorConfig::default();
+    let mut processor = TransactionProcessor::new(&config);
+    let mut records = AppendedRecords::new(&path);
+    records.read_into(&mut processor).unwrap();
+
+    append(&path, "withdrawal,1,2,20\n");
+    assert_eq!(
+        records.read_into(&mut processor).unwrap(),
+        Vec::<u16>::new()
+    );
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(processor.skipped().len(), 1);
+}
+
+#[test]
+fn watch_reports_clients_changed_by_appends() {
+    let path = temp_file("watch", "type,client,tx,amount\ndeposit,1,1,10\n");
+    let (sender, receiver) = mpsc::channel();
+
+    let watched = path.clone();
+    let watcher = thread::spawn(move || {
+        let config = ProcessorConfig::default();
+        let mut processor = TransactionProcessor::new(&config);
+        watch_file(&watched, &mut processor, |_, changed| {
+            sender.send(changed.to_vec()).unwrap();
+            match changed.contains(&2) {
+                true => Err(Error::UnexpectedError("stop watching".into())),
+                false => Ok(()),
+            }
+        })
+    });
+
+    let timeout = Duration::from_secs(10);
+    assert_eq!(receiver.recv_timeout(timeout).unwrap(), vec![1]);
+    append(&path, "deposit,2,2,5\n");
+    assert_eq!(receiver.recv_timeout(timeout).unwrap(), vec![2]);
+
+    assert!(watcher.join().unwrap().is_err());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn unterminated_line_after_many_records_is_left_for_later() {
+    let mut content = String::from("\u{FEFF}type,client,tx,amount\n");
+    for tx in 1..=2_000u32 {
+        content.push_str(&format!("deposit,1,{},1\n", tx));
+    }
+    // longer than the chunks read backwards while looking for the last line break
+    content.push_str(&format!("deposit,2,5000,1{}", "0".repeat(10_000)));
+    let path = temp_file("long_tail", &content);
+    let config = ProcessorConfig::default();
+    let mut processor = TransactionProcessor::new(&config);
+    let mut records = AppendedRecords::new(&path);
+
+    assert_eq!(records.read_into(&mut processor).unwrap(), vec![1]);
+    assert_eq!(
+        records.last_byte_offset(),
+        (content.len() - 10_000 - "deposit,2,5000,1".len()) as u64
+    );
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(processor.client_info()[&1].available, dec!(2000));
+    assert_eq!(processor.rows(), 2_000);
+}
+
+#[test]
+fn merchant_charged_by_a_chargeback_is_reported() {
+    let path = temp_file(
+        "merchant",
+        "type,client,tx,amount\ndeposit,100,1,10\ndeposit,1,2,5\ndispute,1,2,\n",
+    );
+    let config = ProcessorConfig {
+        fee_on_chargeback: Some(dec!(1)),
+        merchant_client_id: Some(100),
+        ..Default::default()
+    };
+    let mut processor = TransactionProcessor::new(&config);
+    let mut records = AppendedRecords::new(&path);
+    records.read_into(&mut processor).unwrap();
+
+    append(&path, "chargeback,1,2,\n");
+    assert_eq!(records.read_into(&mut processor).unwrap(), vec![1, 100]);
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(processor.client_info()[&100].available, dec!(9));
+}
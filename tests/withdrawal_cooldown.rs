@@ -0,0 +1,89 @@
+use rust_decimal_macros::dec;
+use transaction_processor::{process_transactions_from_str, ProcessorConfig, SkipReason};
+
+fn config() -> ProcessorConfig {
+    ProcessorConfig {
+        withdrawal_cooldown_rows: Some(3),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn withdrawal_within_cooldown_is_skipped() {
+    let input = "type,client,tx,amount
+deposit,1,1,100
+withdrawal,1,2,10
+deposit,1,3,5
+withdrawal,1,4,10
+";
+    let report = process_transactions_from_str(input, &config()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(95));
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].tx, 4);
+    assert_eq!(
+        report.skipped[0].reason,
+        SkipReason::WithdrawalCooldown {
+            rows_since: 2,
+            cooldown: 3,
+        }
+    );
+}
+
+#[test]
+fn withdrawal_after_cooldown_is_applied() {
+    let input = "type,client,tx,amount
+deposit,1,1,100
+withdrawal,1,2,10
+deposit,1,3,5
+deposit,1,4,5
+deposit,1,5,5
+withdrawal,1,6,10
+";
+    let report = process_transactions_from_str(input, &config()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(95));
+    assert!(report.skipped.is_empty());
+}
+
+#[test]
+fn cooldown_is_tracked_per_client() {
+    let input = "type,client,tx,amount
+deposit,1,1,100
+deposit,2,2,100
+withdrawal,1,3,10
+withdrawal,2,4,10
+";
+    let report = process_transactions_from_str(input, &config()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(90));
+    assert_eq!(report.client_info[&2].available, dec!(90));
+    assert!(report.skipped.is_empty());
+}
+
+#[test]
+fn transfers_out_share_the_cooldown() {
+    let input = "type,client,tx,amount,target
+deposit,1,1,100
+withdrawal,1,2,10
+transfer,1,3,10,2
+deposit,1,4,5
+deposit,1,5,5
+transfer,1,6,10,2
+withdrawal,1,7,10
+";
+    let report = process_transactions_from_str(input, &config()).unwrap();
+
+    assert_eq!(report.client_info[&1].available, dec!(90));
+    assert_eq!(report.client_info[&2].available, dec!(10));
+    assert_eq!(report.skipped.len(), 2);
+    assert_eq!(report.skipped[0].tx, 3);
+    assert_eq!(report.skipped[1].tx, 7);
+    assert_eq!(
+        report.skipped[1].reason,
+        SkipReason::WithdrawalCooldown {
+            rows_since: 1,
+            cooldown: 3,
+        }
+    );
+}